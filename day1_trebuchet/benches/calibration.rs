@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day1_trebuchet::ascii_scanner::AsciiScanner;
+use day1_trebuchet::extraction::{DigitExtractor, DIGIT_WORDS};
+use day1_trebuchet::solution::{part1, part2};
+
+const INPUT: &str = include_str!("../src/input.txt");
+
+fn sum(extract: impl Fn(&str) -> Option<u32>) -> u32 {
+    INPUT
+        .lines()
+        .map(|line| extract(black_box(line)).unwrap())
+        .sum()
+}
+
+fn bench_part1(c: &mut Criterion) {
+    let mut group = c.benchmark_group("part1");
+    let scanner = AsciiScanner::numerals();
+    let extractor = DigitExtractor::numerals();
+
+    group.bench_function("ascii_scanner", |b| {
+        b.iter(|| sum(|line| scanner.calibration_value(line.as_bytes())))
+    });
+    group.bench_function("digit_extractor", |b| {
+        b.iter(|| sum(|line| extractor.calibration_value(line)))
+    });
+    group.bench_function("extract_calibration_value", |b| {
+        b.iter(|| sum(|line| Some(part1::extract_calibration_value(line))))
+    });
+    group.finish();
+}
+
+fn bench_part2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("part2");
+    let scanner = AsciiScanner::with_words(DIGIT_WORDS.entries());
+    let extractor = DigitExtractor::with_words(&DIGIT_WORDS);
+
+    group.bench_function("ascii_scanner", |b| {
+        b.iter(|| sum(|line| scanner.calibration_value(line.as_bytes())))
+    });
+    group.bench_function("digit_extractor", |b| {
+        b.iter(|| sum(|line| extractor.calibration_value(line)))
+    });
+    group.bench_function("extract_calibration_value", |b| {
+        b.iter(|| sum(|line| Some(part2::extract_calibration_value(line))))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_part1, bench_part2);
+criterion_main!(benches);
@@ -0,0 +1,139 @@
+use memchr::memmem::{Finder, FinderRev};
+
+/// Byte-oriented lookup of calibration values on ASCII lines.
+///
+/// Instead of trying every word at every position like [`DigitExtractor`](crate::extraction::DigitExtractor),
+/// only the first and the last digits are searched for: numerals with a byte scan and words with
+/// `memchr`'s SIMD substring search, each word being looked up before the first numeral and
+/// after the last one only.
+/// Gives the same result as `DigitExtractor` in the default shortest match mode,
+/// as long as no word starts with a numeral.
+///
+/// The numerals are found with a plain loop on purpose: `memchr` looks for at most three bytes
+/// at once, and the four `memchr3` searches the ten numerals need made the `calibration` bench
+/// slower on the puzzle lines, which are at most 65 bytes long (part 1 went from 57µs to 239µs,
+/// part 2 from 446µs to 586µs).
+pub struct AsciiScanner {
+    // sorted by length, so that the shortest word wins when several start at the same position
+    words: Vec<(Finder<'static>, FinderRev<'static>, u32)>,
+}
+
+impl AsciiScanner {
+    pub const fn numerals() -> Self {
+        Self { words: Vec::new() }
+    }
+
+    pub fn with_words(words: &[(&str, u32)]) -> Self {
+        let mut words = words.to_vec();
+        words.sort_by_key(|(word, _)| word.len());
+
+        Self {
+            words: words
+                .into_iter()
+                .map(|(word, value)| {
+                    let finder = Finder::new(word).into_owned();
+                    let finder_rev = FinderRev::new(word).into_owned();
+                    (finder, finder_rev, value)
+                })
+                .collect(),
+        }
+    }
+
+    /// First digit followed by the last digit, `None` if `line` has no digits.
+    pub fn calibration_value(&self, line: &[u8]) -> Option<u32> {
+        let first_numeral = line.iter().position(u8::is_ascii_digit);
+        let last_numeral = line.iter().rposition(u8::is_ascii_digit);
+        let numeral_value = |idx: usize| u32::from(line[idx] - b'0');
+
+        // a word can only come first if it starts before the first numeral
+        let before_first_numeral = &line[..first_numeral.unwrap_or(line.len())];
+        let first_word = self
+            .words
+            .iter()
+            .filter_map(|(finder, _, value)| Some((finder.find(before_first_numeral)?, *value)))
+            .min_by_key(|(idx, _)| *idx);
+
+        // and can only come last if it starts after the last numeral
+        let after_last_numeral = last_numeral.map_or(0, |idx| idx + 1);
+        let last_word = self
+            .words
+            .iter()
+            .filter_map(|(_, finder_rev, value)| {
+                Some((finder_rev.rfind(&line[after_last_numeral..])?, *value))
+            })
+            .min_by_key(|(idx, _)| std::cmp::Reverse(*idx));
+
+        let first = first_word
+            .map(|(_, value)| value)
+            .or(first_numeral.map(numeral_value))?;
+        let last = last_word
+            .map(|(_, value)| value)
+            .or(last_numeral.map(numeral_value))?;
+
+        Some(first * 10 + last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion_trie;
+    use crate::extraction::{DigitExtractor, DIGIT_WORDS};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("two1nine", Some(29))]
+    #[case("eightwothree", Some(83))]
+    #[case("xtwone3four", Some(24))]
+    #[case("7pqrstsixteen", Some(76))]
+    #[case("sevenxx", Some(77))]
+    #[case("six7sixqrdfive3twonehsk", Some(61))]
+    #[case("oneight", Some(18))]
+    #[case("5", Some(55))]
+    #[case("xyz", None)]
+    #[case("", None)]
+    fn test_calibration_value(#[case] input: &str, #[case] expected: Option<u32>) {
+        let scanner = AsciiScanner::with_words(DIGIT_WORDS.entries());
+        assert_eq!(scanner.calibration_value(input.as_bytes()), expected);
+    }
+
+    #[rstest]
+    #[case("a1b2c3d4e5f", Some(15))]
+    #[case("twone", None)]
+    fn test_numerals_only(#[case] input: &str, #[case] expected: Option<u32>) {
+        assert_eq!(
+            AsciiScanner::numerals().calibration_value(input.as_bytes()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_shortest_word_wins() {
+        let entries = [("sixteen", 16), ("six", 6), ("teen", 10)];
+        let scanner = AsciiScanner::with_words(&entries);
+        let words = conversion_trie::ConversionTrie::from_iter(entries);
+        let extractor = DigitExtractor::with_words(&words);
+
+        for input in ["sixteen", "xsixteenx", "1sixteen", "sixteen1"] {
+            assert_eq!(
+                scanner.calibration_value(input.as_bytes()),
+                extractor.calibration_value(input),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_as_digit_extractor_on_input() {
+        let scanner = AsciiScanner::with_words(DIGIT_WORDS.entries());
+        let extractor = DigitExtractor::with_words(&DIGIT_WORDS);
+
+        for line in include_str!("input.txt").lines() {
+            assert_eq!(
+                scanner.calibration_value(line.as_bytes()),
+                extractor.calibration_value(line),
+                "{line}"
+            );
+        }
+    }
+}
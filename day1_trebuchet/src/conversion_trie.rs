@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::mem::size_of;
+use std::ops::Add;
 
 #[derive(Debug, PartialEq)]
 struct Node {
@@ -22,6 +24,38 @@ impl Node {
             subtrie: ConversionTrie::new(input, value),
         }
     }
+
+    fn fuzzy_walk(
+        &self,
+        suffix: char,
+        chars: &[char],
+        parent_row: &[usize],
+        max_distance: usize,
+        key: &mut String,
+        visit: &mut impl FnMut(&str, u32, &[usize]),
+    ) {
+        let mut row = Vec::with_capacity(parent_row.len());
+        row.push(parent_row[0] + 1);
+        for (j, c) in chars.iter().enumerate() {
+            let substitution = parent_row[j] + usize::from(*c != suffix);
+            let insertion = row[j] + 1;
+            let deletion = parent_row[j + 1] + 1;
+            row.push(substitution.min(insertion).min(deletion));
+        }
+
+        // distances only grow deeper down the trie
+        if row.iter().min().is_some_and(|min| *min > max_distance) {
+            return;
+        }
+
+        key.push(suffix);
+        if let Some(value) = self.value {
+            visit(key, value, &row);
+        }
+        self.subtrie
+            .fuzzy_walk(chars, &row, max_distance, key, visit);
+        key.pop();
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -55,6 +89,9 @@ impl ConversionTrie {
         let rest = chars.as_str();
 
         match self.get_child_mut(&head) {
+            // the key ends on an existing node, which can be an intermediate node
+            // of a longer key, so the value must be set on it instead of descending
+            Some(child) if rest.is_empty() => child.value = Some(value),
             Some(child) => child.subtrie.insert(rest, value),
             None => self.add_child(
                 head,
@@ -69,31 +106,103 @@ impl ConversionTrie {
         }
     }
 
-    pub fn convert_head<'a>(&self, input: &'a str) -> Option<(u32, &'a str)> {
-        let mut chars = input.chars();
-
-        // Return None if there are no more characters
-        // Since if any matched value is found,
-        // it must be returned on the previous iteration
-        let Some(head) = chars.next() else {
-            return None;
+    /// Number of nodes below the root and approximate heap usage.
+    pub fn stats(&self) -> TrieStats {
+        // hashbrown keeps one control byte per bucket next to the entries
+        let own = TrieStats {
+            nodes: self.subtree.len(),
+            heap_bytes: self.subtree.capacity() * (size_of::<(char, Node)>() + 1),
         };
 
-        let rest = chars.as_str();
+        self.subtree
+            .values()
+            .map(|child| child.subtrie.stats())
+            .fold(own, |acc, curr| acc + curr)
+    }
 
-        // check if the current character is a child
-        // if it is, continue searching with the rest
-        // else return false
-        match self.get_child(&head) {
-            Some(child) => {
-                // if the child has value, that means pattern is matched
-                // return the value
-                match child.value {
-                    Some(value) => Some((value, rest)),
-                    None => child.subtrie.convert_head(rest),
-                }
-            }
-            None => None,
+    /// Find the key closest to `input` within `max_distance` edits (Levenshtein distance).
+    ///
+    /// Ties are broken by preferring, in order:
+    /// 1. the key with the fewest edits
+    /// 2. the key whose length is the closest to the length of `input`
+    /// 3. the lexicographically smallest key
+    pub fn fuzzy_lookup(&self, input: &str, max_distance: usize) -> Option<FuzzyMatch> {
+        let chars: Vec<char> = input.chars().collect();
+        let first_row: Vec<usize> = (0..=chars.len()).collect();
+
+        let mut best = None;
+        self.fuzzy_walk(
+            &chars,
+            &first_row,
+            max_distance,
+            &mut String::new(),
+            &mut |key, value, row| {
+                let candidate = (row[chars.len()], chars.len());
+                FuzzyMatch::keep_best(&mut best, key, value, candidate, max_distance);
+            },
+        );
+
+        best.map(|(fuzzy_match, _)| fuzzy_match)
+    }
+
+    /// Convert the head of `input` using the key closest to any of its prefixes,
+    /// within `max_distance` edits, returning the match and the remaining input.
+    ///
+    /// The first character must match exactly, which keeps short keys from matching
+    /// almost anywhere on a line. For each key, the prefix with the fewest edits is
+    /// picked (the one closest in length on ties), then keys are compared the same
+    /// way as in [`ConversionTrie::fuzzy_lookup`].
+    pub fn fuzzy_convert_head<'a>(
+        &self,
+        input: &'a str,
+        max_distance: usize,
+    ) -> Option<(FuzzyMatch, &'a str)> {
+        let chars: Vec<char> = input.chars().collect();
+        let first_row: Vec<usize> = (0..=chars.len()).collect();
+        let head = *chars.first()?;
+
+        let mut best = None;
+        self.get_child(&head)?.fuzzy_walk(
+            head,
+            &chars,
+            &first_row,
+            max_distance,
+            &mut String::new(),
+            &mut |key, value, row| {
+                let key_len = key.chars().count();
+                let Some(prefix_len) =
+                    (1..row.len()).min_by_key(|&len| (row[len], key_len.abs_diff(len), !len))
+                else {
+                    return;
+                };
+
+                let candidate = (row[prefix_len], prefix_len);
+                FuzzyMatch::keep_best(&mut best, key, value, candidate, max_distance);
+            },
+        );
+
+        best.map(|(fuzzy_match, prefix_len)| {
+            let rest = input
+                .char_indices()
+                .nth(prefix_len)
+                .map_or("", |(offset, _)| &input[offset..]);
+
+            (fuzzy_match, rest)
+        })
+    }
+
+    // depth-first search keeping one row of the edit distance matrix per node,
+    // `row[j]` being the distance between the current key prefix and `chars[..j]`
+    fn fuzzy_walk(
+        &self,
+        chars: &[char],
+        row: &[usize],
+        max_distance: usize,
+        key: &mut String,
+        visit: &mut impl FnMut(&str, u32, &[usize]),
+    ) {
+        for (suffix, child) in &self.subtree {
+            child.fuzzy_walk(*suffix, chars, row, max_distance, key, visit);
         }
     }
 
@@ -110,6 +219,202 @@ impl ConversionTrie {
     }
 }
 
+impl<'k> FromIterator<(&'k str, u32)> for ConversionTrie {
+    fn from_iter<I: IntoIterator<Item = (&'k str, u32)>>(iter: I) -> Self {
+        let mut tree = Self::empty();
+        for (input, value) in iter {
+            tree.insert(input, value);
+        }
+        tree
+    }
+}
+
+impl Convert for ConversionTrie {
+    type PrefixMatches<'t, 'a> = PrefixMatches<'t, 'a>;
+
+    fn prefix_matches<'t, 'a>(&'t self, input: &'a str) -> PrefixMatches<'t, 'a> {
+        PrefixMatches {
+            trie: Some(self),
+            rest: input,
+        }
+    }
+}
+
+/// Size of a trie, to compare representations
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TrieStats {
+    pub nodes: usize,
+    /// approximate number of bytes allocated on the heap
+    pub heap_bytes: usize,
+}
+
+impl Add for TrieStats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            nodes: self.nodes + rhs.nodes,
+            heap_bytes: self.heap_bytes + rhs.heap_bytes,
+        }
+    }
+}
+
+/// A key found within some edit distance of the searched text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub key: String,
+    pub value: u32,
+    pub distance: usize,
+}
+
+impl FuzzyMatch {
+    // keep the best of the current match and a candidate `(distance, matched_len)`,
+    // along with the number of characters of the searched text it matched
+    fn keep_best(
+        best: &mut Option<(FuzzyMatch, usize)>,
+        key: &str,
+        value: u32,
+        (distance, matched_len): (usize, usize),
+        max_distance: usize,
+    ) {
+        if distance > max_distance {
+            return;
+        }
+
+        let rank = |key: &str, distance: usize, matched_len: usize| {
+            (distance, key.chars().count().abs_diff(matched_len))
+        };
+
+        let is_better = match best {
+            None => true,
+            Some((current, current_len)) => {
+                let current_rank = rank(&current.key, current.distance, *current_len);
+                let candidate_rank = rank(key, distance, matched_len);
+                (candidate_rank, key) < (current_rank, current.key.as_str())
+            }
+        };
+
+        if is_better {
+            *best = Some((
+                FuzzyMatch {
+                    key: key.to_string(),
+                    value,
+                    distance,
+                },
+                matched_len,
+            ));
+        }
+    }
+}
+
+/// Converts the head of an input by matching it against a set of keys
+pub trait Convert {
+    type PrefixMatches<'t, 'a>: Iterator<Item = (u32, &'a str)>
+    where
+        Self: 't;
+
+    /// Iterate over every key that is a prefix of `input`, shortest first,
+    /// yielding the converted value and the remaining input for each of them.
+    fn prefix_matches<'t, 'a>(&'t self, input: &'a str) -> Self::PrefixMatches<'t, 'a>;
+
+    /// Iterate over the keys matching the head of `input` according to `mode`.
+    fn matches<'t, 'a>(
+        &'t self,
+        input: &'a str,
+        mode: MatchMode,
+    ) -> Matches<Self::PrefixMatches<'t, 'a>> {
+        Matches::new(self.prefix_matches(input), mode)
+    }
+
+    /// Convert the head of `input` using the shortest matching key,
+    /// returning the converted value and the remaining input.
+    fn convert_head<'a>(&self, input: &'a str) -> Option<(u32, &'a str)> {
+        self.matches(input, MatchMode::Shortest).next()
+    }
+}
+
+/// Which of the keys matching the head of an input are reported
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    /// only the shortest matching key, e.g. "six" for "sixteen"
+    #[default]
+    Shortest,
+    /// only the longest matching key, e.g. "sixteen" for "sixteen"
+    Longest,
+    /// every matching key, shortest first
+    AllPrefixes,
+}
+
+/// Walks down the trie following the input, yielding every completed key on the way.
+pub struct PrefixMatches<'t, 'a> {
+    trie: Option<&'t ConversionTrie>,
+    rest: &'a str,
+}
+
+impl<'t, 'a> Iterator for PrefixMatches<'t, 'a> {
+    type Item = (u32, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(trie) = self.trie {
+            let mut chars = self.rest.chars();
+
+            // stop walking once the input or the trie is exhausted
+            let Some(child) = chars.next().and_then(|head| trie.get_child(&head)) else {
+                self.trie = None;
+                break;
+            };
+
+            self.rest = chars.as_str();
+            self.trie = Some(&child.subtrie);
+
+            if let Some(value) = child.value {
+                return Some((value, self.rest));
+            }
+        }
+
+        None
+    }
+}
+
+/// Filters prefix matches (shortest first) down to the ones selected by a [`MatchMode`].
+pub struct Matches<I> {
+    prefixes: I,
+    mode: MatchMode,
+    exhausted: bool,
+}
+
+impl<I> Matches<I> {
+    pub fn new(prefixes: I, mode: MatchMode) -> Self {
+        Self {
+            prefixes,
+            mode,
+            exhausted: false,
+        }
+    }
+}
+
+impl<T, I: Iterator<Item = T>> Iterator for Matches<I> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.exhausted {
+            return None;
+        }
+
+        match self.mode {
+            MatchMode::AllPrefixes => self.prefixes.next(),
+            MatchMode::Shortest => {
+                self.exhausted = true;
+                self.prefixes.next()
+            }
+            MatchMode::Longest => {
+                self.exhausted = true;
+                self.prefixes.by_ref().last()
+            }
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! conversion_trie {
     ( $( $x:expr => $y:expr ),* ) => {
@@ -125,6 +430,8 @@ macro_rules! conversion_trie {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_suffix_tree() {
@@ -145,4 +452,116 @@ mod tests {
         assert_eq!(tree.convert_head("twenty"), None);
         assert_eq!(tree.convert_head("three1two"), Some((3, "1two")));
     }
+
+    #[rstest]
+    #[case(&["six", "sixteen"])]
+    #[case(&["sixteen", "six"])]
+    fn test_prefix_related_keys(#[case] keys: &[&str]) {
+        let mut tree = ConversionTrie::empty();
+        for key in keys {
+            tree.insert(key, key.len() as u32);
+        }
+
+        let matches = |input, mode| tree.matches(input, mode).collect::<Vec<_>>();
+
+        assert_eq!(matches("sixteens", MatchMode::Shortest), vec![(3, "teens")]);
+        assert_eq!(matches("sixteens", MatchMode::Longest), vec![(7, "s")]);
+        assert_eq!(
+            matches("sixteens", MatchMode::AllPrefixes),
+            vec![(3, "teens"), (7, "s")]
+        );
+
+        assert_eq!(matches("sixty", MatchMode::Shortest), vec![(3, "ty")]);
+        assert_eq!(matches("sixty", MatchMode::Longest), vec![(3, "ty")]);
+        assert_eq!(matches("sixty", MatchMode::AllPrefixes), vec![(3, "ty")]);
+
+        assert_eq!(matches("si", MatchMode::AllPrefixes), vec![]);
+        assert_eq!(matches("seven", MatchMode::Longest), vec![]);
+    }
+
+    #[test]
+    fn test_reinsert_overrides_value() {
+        let mut tree = conversion_trie! { "eighteen" => 18, "eight" => 8 };
+        tree.insert("eight", 80);
+
+        assert_eq!(tree.convert_head("eighteen"), Some((80, "een")));
+        assert_eq!(
+            tree.matches("eighteen", MatchMode::Longest).next(),
+            Some((18, ""))
+        );
+    }
+
+    fn digit_words() -> ConversionTrie {
+        conversion_trie! {
+            "one" => 1,
+            "two" => 2,
+            "three" => 3,
+            "four" => 4,
+            "five" => 5,
+            "six" => 6,
+            "seven" => 7,
+            "eight" => 8,
+            "nine" => 9
+        }
+    }
+
+    #[rstest]
+    #[case("seven", 0, Some(("seven", 0)))]
+    #[case("sevn", 1, Some(("seven", 1)))]
+    #[case("svn", 1, None)]
+    // "seven" is two insertions away too, but "six" has the same length as the input
+    #[case("svn", 2, Some(("six", 2)))]
+    #[case("eigth", 1, None)]
+    #[case("eigth", 2, Some(("eight", 2)))]
+    #[case("fiv", 1, Some(("five", 1)))]
+    #[case("for", 1, Some(("four", 1)))]
+    #[case("twoo", 1, Some(("two", 1)))]
+    #[case("sx", 1, Some(("six", 1)))]
+    // "nine" is one deletion away too, but "one" has the same length as the input
+    #[case("ine", 1, Some(("one", 1)))]
+    // "one" and "two" are both a substitution away, "one" is lexicographically smaller
+    #[case("owe", 1, Some(("one", 1)))]
+    #[case("xyz", 1, None)]
+    fn test_fuzzy_lookup(
+        #[case] input: &str,
+        #[case] max_distance: usize,
+        #[case] expected: Option<(&str, usize)>,
+    ) {
+        let tree = digit_words();
+        let found = tree.fuzzy_lookup(input, max_distance);
+
+        assert_eq!(
+            found
+                .as_ref()
+                .map(|found| (found.key.as_str(), found.distance)),
+            expected
+        );
+        if let Some(found) = found {
+            assert_eq!(tree.convert_head(&found.key), Some((found.value, "")));
+        }
+    }
+
+    #[rstest]
+    #[case("sevn2", 1, Some((7, 1, "2")))]
+    #[case("seven2", 1, Some((7, 0, "2")))]
+    // dropping the "h" is a single edit, swapping "th" takes two
+    #[case("eigth7", 1, Some((8, 1, "h7")))]
+    // the closest prefix wins, even if it swallows what follows
+    #[case("thre3", 1, Some((3, 1, "")))]
+    #[case("nien", 2, Some((9, 1, "n")))]
+    // the first character must match
+    #[case("xeven", 1, None)]
+    #[case("", 1, None)]
+    fn test_fuzzy_convert_head(
+        #[case] input: &str,
+        #[case] max_distance: usize,
+        #[case] expected: Option<(u32, usize, &str)>,
+    ) {
+        let found = digit_words().fuzzy_convert_head(input, max_distance);
+
+        assert_eq!(
+            found.map(|(found, rest)| (found.value, found.distance, rest)),
+            expected
+        );
+    }
 }
@@ -0,0 +1,507 @@
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+use std::vec;
+
+use crate::conversion_trie::{ConversionTrie, Convert, FuzzyMatch, MatchMode, Matches};
+use crate::static_conversion_trie;
+use crate::static_conversion_trie::StaticConversionTrie;
+use crate::unicode::{decimal_digit_value, Normalization, NormalizedText};
+
+pub const DIGIT_WORDS: StaticConversionTrie = static_conversion_trie! {
+    "one" => 1,
+    "two" => 2,
+    "three" => 3,
+    "four" => 4,
+    "five" => 5,
+    "six" => 6,
+    "seven" => 7,
+    "eight" => 8,
+    "nine" => 9
+};
+
+/// What a recognised digit was written as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitSource<'a> {
+    Numeral(char),
+    Word(&'a str),
+    /// text matched to a word within some edit distance
+    FuzzyWord {
+        text: &'a str,
+        distance: usize,
+    },
+}
+
+/// A digit recognised on a calibration line, with its byte span on that line.
+/// The source always refers to the line as given, even when it was normalized for matching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitSpan<'a> {
+    pub span: Range<usize>,
+    pub value: u32,
+    pub source: DigitSource<'a>,
+}
+
+type FuzzyConvertHead<C> = for<'a> fn(&C, &'a str, usize) -> Option<(FuzzyMatch, &'a str)>;
+
+/// Recognises numerals and, optionally, words converted by a trie on calibration lines.
+#[derive(Debug)]
+pub struct DigitExtractor<'t, C = StaticConversionTrie> {
+    words: Option<&'t C>,
+    mode: MatchMode,
+    // maximum edit distance, with the trie lookup supporting it
+    fuzzy: Option<(usize, FuzzyConvertHead<C>)>,
+    normalization: Normalization,
+    unicode_digits: bool,
+}
+
+impl<C> Clone for DigitExtractor<'_, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for DigitExtractor<'_, C> {}
+
+impl DigitExtractor<'static> {
+    pub const fn numerals() -> Self {
+        Self {
+            words: None,
+            mode: MatchMode::Shortest,
+            fuzzy: None,
+            normalization: Normalization::None,
+            unicode_digits: false,
+        }
+    }
+}
+
+impl<'t, C: Convert> DigitExtractor<'t, C> {
+    pub const fn with_words(words: &'t C) -> Self {
+        Self {
+            words: Some(words),
+            mode: MatchMode::Shortest,
+            fuzzy: None,
+            normalization: Normalization::None,
+            unicode_digits: false,
+        }
+    }
+
+    /// Set which of the words starting at the same position are reported,
+    /// the shortest one by default.
+    pub const fn match_mode(self, mode: MatchMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// Bring lines to a Unicode normal form before matching, none by default.
+    /// Words are matched against the normalized line, so they must be normalized the same way.
+    pub const fn normalization(self, normalization: Normalization) -> Self {
+        Self {
+            normalization,
+            ..self
+        }
+    }
+
+    /// Recognise decimal digits of any script as numerals, e.g. '３' or '٣',
+    /// instead of ASCII digits only.
+    pub const fn unicode_digits(self) -> Self {
+        Self {
+            unicode_digits: true,
+            ..self
+        }
+    }
+
+    /// Iterate over every digit recognised on `input`, ordered by start position.
+    /// Words can overlap each other, e.g. "twone" yields both "two" and "one".
+    pub fn digits<'a>(&self, input: &'a str) -> Digits<'t, 'a, C> {
+        if self.normalization == Normalization::None {
+            return Digits::Scan(DigitScan::new(*self, input));
+        }
+
+        // digits found on the normalized line are mapped back to the line as given
+        let text = NormalizedText::new(input, self.normalization);
+        let digits: Vec<_> = DigitScan::new(*self, text.as_str())
+            .map(|digit| {
+                let span = text.original_span(digit.span);
+                let original = &input[span.clone()];
+
+                let source = match digit.source {
+                    DigitSource::Numeral(c) => {
+                        DigitSource::Numeral(original.chars().next().unwrap_or(c))
+                    }
+                    DigitSource::Word(_) => DigitSource::Word(original),
+                    DigitSource::FuzzyWord { distance, .. } => DigitSource::FuzzyWord {
+                        text: original,
+                        distance,
+                    },
+                };
+
+                DigitSpan {
+                    span,
+                    value: digit.value,
+                    source,
+                }
+            })
+            .collect();
+
+        Digits::Normalized(digits.into_iter())
+    }
+
+    /// First digit followed by the last digit, `None` if `input` has no digits.
+    pub fn calibration_value(&self, input: &str) -> Option<u32> {
+        let mut digits = self.digits(input);
+        let first = digits.next()?;
+        let last = digits.last().unwrap_or_else(|| first.clone());
+
+        Some(first.value * 10 + last.value)
+    }
+
+    fn digit_value(&self, c: char) -> Option<u32> {
+        if self.unicode_digits {
+            decimal_digit_value(c)
+        } else {
+            c.to_digit(10)
+        }
+    }
+}
+
+impl<'t> DigitExtractor<'t, ConversionTrie> {
+    /// Also recognise misspelled words within `max_distance` edits of a word,
+    /// see [`ConversionTrie::fuzzy_convert_head`]. Words matching exactly are always preferred.
+    pub fn fuzzy(self, max_distance: usize) -> Self {
+        Self {
+            fuzzy: Some((max_distance, ConversionTrie::fuzzy_convert_head)),
+            ..self
+        }
+    }
+}
+
+pub enum Digits<'t, 'a, C: Convert + 't> {
+    Scan(DigitScan<'t, 'a, C>),
+    Normalized(vec::IntoIter<DigitSpan<'a>>),
+}
+
+impl<'t, 'a, C: Convert + 't> Iterator for Digits<'t, 'a, C> {
+    type Item = DigitSpan<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Digits::Scan(scan) => scan.next(),
+            Digits::Normalized(digits) => digits.next(),
+        }
+    }
+}
+
+/// Scans a line as is, one position at a time
+pub struct DigitScan<'t, 'a, C: Convert + 't> {
+    extractor: DigitExtractor<'t, C>,
+    input: &'a str,
+    chars: CharIndices<'a>,
+    // words matched at the current position, with that position
+    word_matches: Option<(usize, Peekable<Matches<C::PrefixMatches<'t, 'a>>>)>,
+    // end of the furthest word reported so far
+    covered_until: usize,
+}
+
+impl<'t, 'a, C: Convert + 't> DigitScan<'t, 'a, C> {
+    fn new(extractor: DigitExtractor<'t, C>, input: &'a str) -> Self {
+        Self {
+            extractor,
+            input,
+            chars: input.char_indices(),
+            word_matches: None,
+            covered_until: 0,
+        }
+    }
+}
+
+impl<'t, 'a, C: Convert + 't> Iterator for DigitScan<'t, 'a, C> {
+    type Item = DigitSpan<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((start, matches)) = &mut self.word_matches {
+                if let Some((value, rest)) = matches.next() {
+                    let span = *start..self.input.len() - rest.len();
+                    let word = &self.input[span.clone()];
+                    self.covered_until = self.covered_until.max(span.end);
+
+                    return Some(DigitSpan {
+                        span,
+                        value,
+                        source: DigitSource::Word(word),
+                    });
+                }
+            }
+
+            let (start, c) = self.chars.next()?;
+            let DigitExtractor {
+                words, mode, fuzzy, ..
+            } = self.extractor;
+
+            // words are reported after the numeral starting at the same position
+            let mut word_matches =
+                words.map(|words| words.matches(&self.input[start..], mode).peekable());
+            let any_word_match = word_matches
+                .as_mut()
+                .is_some_and(|matches| matches.peek().is_some());
+            self.word_matches = word_matches.map(|matches| (start, matches));
+
+            if let Some(value) = self.extractor.digit_value(c) {
+                return Some(DigitSpan {
+                    span: start..start + c.len_utf8(),
+                    value,
+                    source: DigitSource::Numeral(c),
+                });
+            }
+
+            // fuzzy matching only fills the gaps left between numerals and words,
+            // and never swallows a numeral
+            let (Some(words), Some((max_distance, fuzzy_convert_head))) = (words, fuzzy) else {
+                continue;
+            };
+            if any_word_match || start < self.covered_until {
+                continue;
+            }
+
+            let gap_end = self.input[start..]
+                .find(|c: char| self.extractor.digit_value(c).is_some())
+                .map_or(self.input.len(), |offset| start + offset);
+
+            let gap = &self.input[start..gap_end];
+            if let Some((found, rest)) = fuzzy_convert_head(words, gap, max_distance) {
+                let span = start..gap_end - rest.len();
+                self.covered_until = span.end;
+
+                return Some(DigitSpan {
+                    source: DigitSource::FuzzyWord {
+                        text: &self.input[span.clone()],
+                        distance: found.distance,
+                    },
+                    span,
+                    value: found.value,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion_trie;
+    use rstest::rstest;
+
+    fn spans<'a>(digits: impl Iterator<Item = DigitSpan<'a>>) -> Vec<(Range<usize>, u32)> {
+        digits.map(|digit| (digit.span, digit.value)).collect()
+    }
+
+    #[test]
+    fn test_digits_with_words() {
+        let extractor = DigitExtractor::with_words(&DIGIT_WORDS);
+
+        assert_eq!(
+            extractor.digits("xtwone3four").collect::<Vec<_>>(),
+            vec![
+                DigitSpan {
+                    span: 1..4,
+                    value: 2,
+                    source: DigitSource::Word("two")
+                },
+                DigitSpan {
+                    span: 3..6,
+                    value: 1,
+                    source: DigitSource::Word("one")
+                },
+                DigitSpan {
+                    span: 6..7,
+                    value: 3,
+                    source: DigitSource::Numeral('3')
+                },
+                DigitSpan {
+                    span: 7..11,
+                    value: 4,
+                    source: DigitSource::Word("four")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_digits_numerals_only() {
+        let extractor = DigitExtractor::numerals();
+
+        assert_eq!(
+            spans(extractor.digits("xtwone3four5")),
+            vec![(6..7, 3), (11..12, 5)]
+        );
+        assert_eq!(spans(extractor.digits("twone")), vec![]);
+    }
+
+    #[test]
+    fn test_spans_are_byte_offsets() {
+        let extractor = DigitExtractor::with_words(&DIGIT_WORDS);
+
+        assert_eq!(
+            spans(extractor.digits("é1→two")),
+            vec![(2..3, 1), (6..9, 2)]
+        );
+    }
+
+    #[rstest]
+    #[case(MatchMode::Shortest, vec![(0..3, 6), (7..8, 7)])]
+    #[case(MatchMode::Longest, vec![(0..7, 16), (7..8, 7)])]
+    #[case(MatchMode::AllPrefixes, vec![(0..3, 6), (0..7, 16), (7..8, 7)])]
+    fn test_digits_match_mode(#[case] mode: MatchMode, #[case] expected: Vec<(Range<usize>, u32)>) {
+        let words = conversion_trie! { "six" => 6, "sixteen" => 16 };
+        let extractor = DigitExtractor::with_words(&words).match_mode(mode);
+
+        assert_eq!(spans(extractor.digits("sixteen7")), expected);
+    }
+
+    #[rstest]
+    #[case("abc", None)]
+    #[case("a7b", Some(77))]
+    #[case("eightwo", Some(82))]
+    fn test_calibration_value(#[case] input: &str, #[case] expected: Option<u32>) {
+        let extractor = DigitExtractor::with_words(&DIGIT_WORDS);
+        assert_eq!(extractor.calibration_value(input), expected);
+    }
+
+    fn digit_words() -> ConversionTrie {
+        DIGIT_WORDS.entries().iter().copied().collect()
+    }
+
+    #[test]
+    fn test_fuzzy_digits() {
+        let words = digit_words();
+        let extractor = DigitExtractor::with_words(&words).fuzzy(1);
+
+        assert_eq!(
+            extractor.digits("sevn2eigth").collect::<Vec<_>>(),
+            vec![
+                DigitSpan {
+                    span: 0..4,
+                    value: 7,
+                    source: DigitSource::FuzzyWord {
+                        text: "sevn",
+                        distance: 1
+                    }
+                },
+                DigitSpan {
+                    span: 4..5,
+                    value: 2,
+                    source: DigitSource::Numeral('2')
+                },
+                DigitSpan {
+                    span: 5..9,
+                    value: 8,
+                    source: DigitSource::FuzzyWord {
+                        text: "eigt",
+                        distance: 1
+                    }
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("sevn2eigth", 0, Some(22))]
+    #[case("sevn2eigth", 1, Some(78))]
+    #[case("fourr", 1, Some(44))]
+    #[case("thre3", 1, Some(33))]
+    #[case("onne", 1, Some(11))]
+    #[case("xtwone3four", 1, Some(24))]
+    // fuzzy matches never overlap a word, unlike exact ones
+    #[case("twonx", 1, Some(22))]
+    #[case("xyz", 1, None)]
+    #[case("nien", 1, Some(99))]
+    fn test_fuzzy_calibration_value(
+        #[case] input: &str,
+        #[case] max_distance: usize,
+        #[case] expected: Option<u32>,
+    ) {
+        let words = digit_words();
+        let extractor = DigitExtractor::with_words(&words).fuzzy(max_distance);
+
+        assert_eq!(extractor.calibration_value(input), expected);
+    }
+
+    #[test]
+    fn test_normalized_digits() {
+        let extractor = DigitExtractor::with_words(&DIGIT_WORDS).normalization(Normalization::Nfkc);
+
+        assert_eq!(
+            extractor.digits("ｆｉｖｅ３").collect::<Vec<_>>(),
+            vec![
+                DigitSpan {
+                    span: 0..12,
+                    value: 5,
+                    source: DigitSource::Word("ｆｉｖｅ")
+                },
+                DigitSpan {
+                    span: 12..15,
+                    value: 3,
+                    source: DigitSource::Numeral('３')
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(Normalization::None, "ﬁve①", vec![])]
+    #[case(Normalization::Nfc, "ﬁve①", vec![])]
+    #[case(Normalization::Nfkc, "ﬁve①", vec![(0..5, 5), (5..8, 1)])]
+    #[case(Normalization::Nfkc, "x２ｔｗｏ", vec![(1..4, 2), (4..13, 2)])]
+    #[case(Normalization::Nfc, "nine\u{301}", vec![])]
+    fn test_normalization(
+        #[case] normalization: Normalization,
+        #[case] input: &str,
+        #[case] expected: Vec<(Range<usize>, u32)>,
+    ) {
+        let extractor = DigitExtractor::with_words(&DIGIT_WORDS).normalization(normalization);
+        assert_eq!(spans(extractor.digits(input)), expected);
+    }
+
+    #[test]
+    fn test_words_keyed_on_normalized_text() {
+        let words: ConversionTrie = [("dieciséis", 16), ("seis", 6)].into_iter().collect();
+        let decomposed = "xdiecise\u{301}is7";
+
+        let extractor = DigitExtractor::with_words(&words);
+        assert_eq!(spans(extractor.digits(decomposed)), vec![(12..13, 7)]);
+
+        let extractor = extractor.normalization(Normalization::Nfc);
+        assert_eq!(
+            spans(extractor.digits(decomposed)),
+            vec![(1..12, 16), (12..13, 7)]
+        );
+        assert_eq!(
+            extractor.digits(decomposed).next().unwrap().source,
+            DigitSource::Word("diecise\u{301}is")
+        );
+    }
+
+    #[rstest]
+    #[case("٣x７", false, vec![])]
+    #[case("٣x７", true, vec![(0..2, 3), (3..6, 7)])]
+    #[case("৪two", true, vec![(0..3, 4), (3..6, 2)])]
+    fn test_unicode_digits(
+        #[case] input: &str,
+        #[case] unicode_digits: bool,
+        #[case] expected: Vec<(Range<usize>, u32)>,
+    ) {
+        let mut extractor = DigitExtractor::with_words(&DIGIT_WORDS);
+        if unicode_digits {
+            extractor = extractor.unicode_digits();
+        }
+
+        assert_eq!(spans(extractor.digits(input)), expected);
+    }
+
+    #[test]
+    fn test_fuzzy_matching_stops_at_unicode_digits() {
+        let words = digit_words();
+        let extractor = DigitExtractor::with_words(&words).fuzzy(1).unicode_digits();
+
+        assert_eq!(spans(extractor.digits("thre٣")), vec![(0..4, 3), (4..6, 3)]);
+    }
+}
@@ -1,2 +1,7 @@
-mod conversion_trie;
+pub mod ascii_scanner;
+pub mod conversion_trie;
+pub mod extraction;
+pub mod radix_conversion_trie;
 pub mod solution;
+pub mod static_conversion_trie;
+pub mod unicode;
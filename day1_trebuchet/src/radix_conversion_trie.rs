@@ -0,0 +1,309 @@
+use std::mem::size_of;
+
+use crate::conversion_trie::{Convert, TrieStats};
+
+/// Path-compressed counterpart of [`ConversionTrie`](crate::conversion_trie::ConversionTrie).
+///
+/// Chains of nodes with a single child and no value are merged into one edge labelled
+/// with the whole chain, and children are kept in a sorted `Vec` instead of a `HashMap`,
+/// which keeps large vocabularies small in memory.
+#[derive(Debug, Default, PartialEq)]
+pub struct RadixConversionTrie {
+    root: RadixNode,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct RadixNode {
+    value: Option<u32>,
+    // sorted by label, no two labels start with the same character
+    edges: Vec<Edge>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Edge {
+    label: Box<str>,
+    node: RadixNode,
+}
+
+impl RadixConversionTrie {
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn new(input: &str, value: u32) -> Self {
+        let mut tree = Self::empty();
+        tree.insert(input, value);
+        tree
+    }
+
+    pub fn insert(&mut self, input: &str, value: u32) {
+        // same as `ConversionTrie`, empty keys are ignored
+        if !input.is_empty() {
+            self.root.insert(input, value);
+        }
+    }
+
+    /// Number of nodes below the root and approximate heap usage.
+    pub fn stats(&self) -> TrieStats {
+        self.root.stats()
+    }
+}
+
+impl RadixNode {
+    fn leaf(value: u32) -> Self {
+        Self {
+            value: Some(value),
+            edges: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, input: &str, value: u32) {
+        let Some(head) = input.chars().next() else {
+            self.value = Some(value);
+            return;
+        };
+
+        let edge_idx = match self.find_edge(head) {
+            Ok(edge_idx) => edge_idx,
+            Err(insert_at) => {
+                let edge = Edge {
+                    label: input.into(),
+                    node: Self::leaf(value),
+                };
+                self.edges.insert(insert_at, edge);
+                return;
+            }
+        };
+
+        let edge = &mut self.edges[edge_idx];
+        let common = common_prefix_len(&edge.label, input);
+
+        // split the edge where the key diverges from its label
+        if common < edge.label.len() {
+            let suffix = Edge {
+                label: edge.label[common..].into(),
+                node: std::mem::take(&mut edge.node),
+            };
+            edge.label = edge.label[..common].into();
+            edge.node.edges.push(suffix);
+        }
+
+        edge.node.insert(&input[common..], value);
+    }
+
+    fn find_edge(&self, head: char) -> Result<usize, usize> {
+        self.edges
+            .binary_search_by_key(&head, |edge| first_char(&edge.label))
+    }
+
+    fn stats(&self) -> TrieStats {
+        let own = TrieStats {
+            nodes: self.edges.len(),
+            heap_bytes: self.edges.capacity() * size_of::<Edge>()
+                + self
+                    .edges
+                    .iter()
+                    .map(|edge| edge.label.len())
+                    .sum::<usize>(),
+        };
+
+        self.edges
+            .iter()
+            .map(|edge| edge.node.stats())
+            .fold(own, |acc, curr| acc + curr)
+    }
+}
+
+impl<'k> FromIterator<(&'k str, u32)> for RadixConversionTrie {
+    fn from_iter<I: IntoIterator<Item = (&'k str, u32)>>(iter: I) -> Self {
+        let mut tree = Self::empty();
+        for (input, value) in iter {
+            tree.insert(input, value);
+        }
+        tree
+    }
+}
+
+impl Convert for RadixConversionTrie {
+    type PrefixMatches<'t, 'a> = RadixPrefixMatches<'t, 'a>;
+
+    fn prefix_matches<'t, 'a>(&'t self, input: &'a str) -> RadixPrefixMatches<'t, 'a> {
+        RadixPrefixMatches {
+            node: Some(&self.root),
+            rest: input,
+        }
+    }
+}
+
+/// Walks down the radix trie following the input, yielding every completed key on the way.
+pub struct RadixPrefixMatches<'t, 'a> {
+    node: Option<&'t RadixNode>,
+    rest: &'a str,
+}
+
+impl<'t, 'a> Iterator for RadixPrefixMatches<'t, 'a> {
+    type Item = (u32, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.node {
+            // the whole label must be consumed to reach the next node
+            let edge = self
+                .rest
+                .chars()
+                .next()
+                .and_then(|head| node.find_edge(head).ok())
+                .map(|edge_idx| &node.edges[edge_idx])
+                .filter(|edge| self.rest.starts_with(&*edge.label));
+
+            let Some(edge) = edge else {
+                self.node = None;
+                break;
+            };
+
+            self.rest = &self.rest[edge.label.len()..];
+            self.node = Some(&edge.node);
+
+            if let Some(value) = edge.node.value {
+                return Some((value, self.rest));
+            }
+        }
+
+        None
+    }
+}
+
+fn first_char(label: &str) -> char {
+    label.chars().next().expect("edge labels are never empty")
+}
+
+// length in bytes of the longest common prefix, always on a char boundary
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(a.len().min(b.len()), |((idx, _), _)| idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion_trie::{ConversionTrie, MatchMode};
+    use rstest::rstest;
+
+    const KEYS: [(&str, u32); 9] = [
+        ("sixteen", 16),
+        ("six", 6),
+        ("sixty", 60),
+        ("seven", 7),
+        ("seventeen", 17),
+        ("sé", 0),
+        ("s", 1),
+        ("one", 1),
+        ("only", 2),
+    ];
+
+    #[test]
+    fn test_radix_trie() {
+        let tree = RadixConversionTrie::from_iter([
+            ("one", 1),
+            ("two", 2),
+            ("three", 3),
+            ("four", 4),
+            ("five", 5),
+            ("six", 6),
+            ("seven", 7),
+            ("eight", 8),
+            ("nine", 9),
+        ]);
+
+        assert_eq!(tree.convert_head("one"), Some((1, "")));
+        assert_eq!(tree.convert_head("1two"), None);
+        assert_eq!(tree.convert_head("twenty"), None);
+        assert_eq!(tree.convert_head("three1two"), Some((3, "1two")));
+        assert_eq!(tree.convert_head("th"), None);
+    }
+
+    #[rstest]
+    #[case("sixteens", MatchMode::Shortest)]
+    #[case("sixteens", MatchMode::Longest)]
+    #[case("sixteens", MatchMode::AllPrefixes)]
+    #[case("sixty", MatchMode::AllPrefixes)]
+    #[case("sixt", MatchMode::AllPrefixes)]
+    #[case("seventeen", MatchMode::Longest)]
+    #[case("séance", MatchMode::AllPrefixes)]
+    #[case("onlyone", MatchMode::AllPrefixes)]
+    #[case("on", MatchMode::AllPrefixes)]
+    fn test_same_matches_as_conversion_trie(#[case] input: &str, #[case] mode: MatchMode) {
+        let trie = ConversionTrie::from_iter(KEYS);
+
+        // edges are split at different points depending on the insertion order
+        for tree in [
+            RadixConversionTrie::from_iter(KEYS),
+            RadixConversionTrie::from_iter(KEYS.into_iter().rev()),
+        ] {
+            assert_eq!(
+                tree.matches(input, mode).collect::<Vec<_>>(),
+                trie.matches(input, mode).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_insertion_order_does_not_matter() {
+        assert_eq!(
+            RadixConversionTrie::from_iter(KEYS),
+            RadixConversionTrie::from_iter(KEYS.into_iter().rev())
+        );
+    }
+
+    #[test]
+    fn test_reinsert_overrides_value() {
+        let mut tree = RadixConversionTrie::from_iter([("eighteen", 18), ("eight", 8)]);
+        tree.insert("eight", 80);
+        tree.insert("", 0);
+
+        assert_eq!(tree.convert_head("eighteen"), Some((80, "een")));
+        assert_eq!(tree.convert_head(""), None);
+    }
+
+    #[test]
+    fn test_stats() {
+        let tree = RadixConversionTrie::from_iter([("six", 6), ("sixteen", 16), ("seven", 7)]);
+
+        // "s" -> { "even", "ix" -> "teen" }
+        assert_eq!(tree.stats().nodes, 4);
+        assert_eq!(
+            ConversionTrie::from_iter([("six", 6), ("sixteen", 16), ("seven", 7)])
+                .stats()
+                .nodes,
+            11
+        );
+    }
+
+    #[test]
+    fn test_large_vocabulary() {
+        let words: Vec<String> = (0..20_000).map(|n| format!("word{n}")).collect();
+        let entries = || words.iter().map(String::as_str).zip(0..);
+
+        let tree = RadixConversionTrie::from_iter(entries());
+        let trie = ConversionTrie::from_iter(entries());
+
+        for (word, value) in entries().step_by(997) {
+            // shorter words are prefixes of longer ones, e.g. "word9" of "word997"
+            assert_eq!(
+                tree.matches(word, MatchMode::Longest).next(),
+                Some((value, ""))
+            );
+            assert_eq!(
+                tree.matches(word, MatchMode::AllPrefixes)
+                    .collect::<Vec<_>>(),
+                trie.matches(word, MatchMode::AllPrefixes)
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        let (radix, plain) = (tree.stats(), trie.stats());
+        assert!(radix.nodes < plain.nodes);
+        assert!(radix.heap_bytes < plain.heap_bytes);
+    }
+}
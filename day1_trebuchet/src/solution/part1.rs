@@ -1,7 +1,17 @@
+use crate::ascii_scanner::AsciiScanner;
+use crate::extraction::DigitExtractor;
+
+static SCANNER: AsciiScanner = AsciiScanner::numerals();
+
 pub fn extract_calibration_value(input: &str) -> u32 {
-    let digits: Vec<u32> = input.chars().filter_map(|c| c.to_digit(10)).collect();
+    // ASCII lines take the byte-oriented fast path
+    let value = if input.is_ascii() {
+        SCANNER.calibration_value(input.as_bytes())
+    } else {
+        DigitExtractor::numerals().calibration_value(input)
+    };
 
-    digits.first().unwrap() * 10 + digits.last().unwrap()
+    value.expect("calibration line has at least one digit")
 }
 
 #[cfg(test)]
@@ -14,6 +24,7 @@ mod tests {
     #[case("pqr3stu8vwx", 38)]
     #[case("a1b2c3d4e5f", 15)]
     #[case("treb7uchet", 77)]
+    #[case("trébuchet7", 77)]
 
     fn test_extract_calibration_value(#[case] input: &str, #[case] expected: u32) {
         assert_eq!(extract_calibration_value(input), expected);
@@ -1,32 +1,20 @@
-use crate::conversion_trie;
+use std::sync::LazyLock;
 
-pub fn extract_calibration_value(input: &str) -> u32 {
-    let tree = conversion_trie! {
-        "one" => 1,
-        "two" => 2,
-        "three" => 3,
-        "four" => 4,
-        "five" => 5,
-        "six" => 6,
-        "seven" => 7,
-        "eight" => 8,
-        "nine" => 9
-    };
+use crate::ascii_scanner::AsciiScanner;
+use crate::extraction::{DigitExtractor, DIGIT_WORDS};
 
-    let mut digits: Vec<u32> = vec![];
+static SCANNER: LazyLock<AsciiScanner> =
+    LazyLock::new(|| AsciiScanner::with_words(DIGIT_WORDS.entries()));
 
-    let mut rem_input = input;
+pub fn extract_calibration_value(input: &str) -> u32 {
+    // ASCII lines take the byte-oriented fast path
+    let value = if input.is_ascii() {
+        SCANNER.calibration_value(input.as_bytes())
+    } else {
+        DigitExtractor::with_words(&DIGIT_WORDS).calibration_value(input)
+    };
 
-    while !rem_input.is_empty() {
-        let mut chars_iter = rem_input.chars();
-        if let Some(digit) = chars_iter.next().expect("non empty string").to_digit(10) {
-            digits.push(digit);
-        } else if let Some((value, _)) = tree.convert_head(rem_input) {
-            digits.push(value);
-        }
-        rem_input = chars_iter.as_str();
-    }
-    digits.first().unwrap() * 10 + digits.last().unwrap()
+    value.expect("calibration line has at least one digit")
 }
 
 #[cfg(test)]
@@ -45,6 +33,7 @@ mod tests {
     #[case("sevenxx", 77)]
     #[case("xxfivexx", 55)]
     #[case("six7sixqrdfive3twonehsk", 61)]
+    #[case("→six7sixqrdfive3twonehsk", 61)]
 
     fn test_extract_calibration_value(#[case] input: &str, #[case] expected: u32) {
         assert_eq!(extract_calibration_value(input), expected);
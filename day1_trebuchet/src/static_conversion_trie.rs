@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+
+use crate::conversion_trie::Convert;
+
+/// Allocation-free counterpart of [`ConversionTrie`](crate::conversion_trie::ConversionTrie).
+///
+/// Keys are kept in a byte-wise sorted slice, so every node of the trie is a contiguous
+/// range of entries sharing the same prefix, and walking down the trie is narrowing
+/// that range with binary searches.
+/// Usually built at compile time with the [`static_conversion_trie!`](crate::static_conversion_trie) macro.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StaticConversionTrie {
+    entries: &'static [(&'static str, u32)],
+}
+
+impl StaticConversionTrie {
+    /// Wrap entries that are already sorted by key, panics (at compile time when used
+    /// in a const context) if they are not sorted or contain duplicated or empty keys.
+    pub const fn from_sorted(entries: &'static [(&'static str, u32)]) -> Self {
+        let mut i = 0;
+        while i < entries.len() {
+            assert!(!entries[i].0.is_empty(), "empty keys are not supported");
+            if i > 0 {
+                assert!(
+                    matches!(compare_keys(entries[i - 1].0, entries[i].0), Ordering::Less),
+                    "keys must be sorted and unique"
+                );
+            }
+            i += 1;
+        }
+
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &'static [(&'static str, u32)] {
+        self.entries
+    }
+}
+
+impl Convert for StaticConversionTrie {
+    type PrefixMatches<'t, 'a> = StaticPrefixMatches<'a>;
+
+    fn prefix_matches<'a>(&self, input: &'a str) -> StaticPrefixMatches<'a> {
+        StaticPrefixMatches {
+            entries: self.entries,
+            input,
+            depth: 0,
+        }
+    }
+}
+
+/// Walks down the sorted entries following the input, yielding every completed key on the way.
+pub struct StaticPrefixMatches<'a> {
+    // entries sharing `input[..depth]` as prefix
+    entries: &'static [(&'static str, u32)],
+    input: &'a str,
+    depth: usize,
+}
+
+impl<'a> Iterator for StaticPrefixMatches<'a> {
+    type Item = (u32, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.entries.is_empty() {
+            let Some(&byte) = self.input.as_bytes().get(self.depth) else {
+                self.entries = &[];
+                break;
+            };
+
+            // keys ending at `depth` sort before any longer key and were already reported,
+            // so `None < Some(byte)` skips them along with the smaller bytes
+            let key_byte = |(key, _): &(&str, u32)| key.as_bytes().get(self.depth).copied();
+            let start = self
+                .entries
+                .partition_point(|entry| key_byte(entry) < Some(byte));
+            let end = self
+                .entries
+                .partition_point(|entry| key_byte(entry) <= Some(byte));
+
+            self.entries = &self.entries[start..end];
+            self.depth += 1;
+
+            // a key completed at this depth is the first entry of the range
+            if let Some(&(key, value)) = self.entries.first() {
+                if key.len() == self.depth {
+                    return Some((value, &self.input[self.depth..]));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Sort entries by key at compile time, panics on duplicated or empty keys.
+#[doc(hidden)]
+pub const fn sort_entries<const N: usize>(
+    mut entries: [(&'static str, u32); N],
+) -> [(&'static str, u32); N] {
+    // insertion sort, as iterators and closures are not available in const fn
+    let mut i = 1;
+    while i < N {
+        let mut j = i;
+        while j > 0 {
+            match compare_keys(entries[j - 1].0, entries[j].0) {
+                Ordering::Less => break,
+                Ordering::Equal => panic!("duplicated key"),
+                Ordering::Greater => {
+                    let tmp = entries[j];
+                    entries[j] = entries[j - 1];
+                    entries[j - 1] = tmp;
+                }
+            }
+            j -= 1;
+        }
+        i += 1;
+    }
+
+    entries
+}
+
+const fn compare_keys(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] < b[i] {
+            return Ordering::Less;
+        }
+        if a[i] > b[i] {
+            return Ordering::Greater;
+        }
+        i += 1;
+    }
+
+    if a.len() < b.len() {
+        Ordering::Less
+    } else if a.len() > b.len() {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+/// Same syntax as [`conversion_trie!`](crate::conversion_trie), but the trie is sorted
+/// at compile time and usable in const context.
+#[macro_export]
+macro_rules! static_conversion_trie {
+    ( $( $x:expr => $y:expr ),* ) => {
+        {
+            const ENTRIES: &[(&str, u32)] =
+                &$crate::static_conversion_trie::sort_entries([$( ($x, $y) ),*]);
+            $crate::static_conversion_trie::StaticConversionTrie::from_sorted(ENTRIES)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion_trie;
+    use crate::conversion_trie::MatchMode;
+    use rstest::rstest;
+
+    const DIGITS: StaticConversionTrie = static_conversion_trie! {
+        "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9
+    };
+
+    #[test]
+    fn test_static_trie() {
+        assert_eq!(DIGITS.convert_head("one"), Some((1, "")));
+        assert_eq!(DIGITS.convert_head("1two"), None);
+        assert_eq!(DIGITS.convert_head("twenty"), None);
+        assert_eq!(DIGITS.convert_head("three1two"), Some((3, "1two")));
+        assert_eq!(DIGITS.convert_head(""), None);
+    }
+
+    #[test]
+    fn test_entries_are_sorted() {
+        let keys: Vec<_> = DIGITS.entries().iter().map(|(key, _)| *key).collect();
+        assert_eq!(
+            keys,
+            vec!["eight", "five", "four", "nine", "one", "seven", "six", "three", "two"]
+        );
+    }
+
+    #[rstest]
+    #[case("sixteens", MatchMode::Shortest)]
+    #[case("sixteens", MatchMode::Longest)]
+    #[case("sixteens", MatchMode::AllPrefixes)]
+    #[case("sixty", MatchMode::AllPrefixes)]
+    #[case("seventeen", MatchMode::Longest)]
+    #[case("sevens", MatchMode::AllPrefixes)]
+    #[case("séance", MatchMode::AllPrefixes)]
+    #[case("s", MatchMode::AllPrefixes)]
+    fn test_same_matches_as_conversion_trie(#[case] input: &str, #[case] mode: MatchMode) {
+        let static_trie = static_conversion_trie! {
+            "sixteen" => 16,
+            "six" => 6,
+            "seventeen" => 17,
+            "seven" => 7,
+            "sé" => 0
+        };
+        let trie = conversion_trie! {
+            "sixteen" => 16,
+            "six" => 6,
+            "seventeen" => 17,
+            "seven" => 7,
+            "sé" => 0
+        };
+
+        assert_eq!(
+            static_trie.matches(input, mode).collect::<Vec<_>>(),
+            trie.matches(input, mode).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use unicode_normalization::char::{canonical_combining_class, decompose_compatible};
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// Unicode normal form calibration lines are brought to before matching
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    #[default]
+    None,
+    /// canonical composition, e.g. "e\u{301}" becomes "é"
+    Nfc,
+    /// compatibility composition, e.g. "ｆｉｖｅ" becomes "five" and "①" becomes "1"
+    Nfkc,
+}
+
+impl Normalization {
+    pub fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            Normalization::None => Cow::Borrowed(text),
+            Normalization::Nfc => Cow::Owned(text.nfc().collect()),
+            Normalization::Nfkc => Cow::Owned(text.nfkc().collect()),
+        }
+    }
+}
+
+/// Text brought to a normal form, remembering which part of the original text
+/// each part of the normalized text comes from.
+#[derive(Debug)]
+pub struct NormalizedText<'a> {
+    original: &'a str,
+    text: String,
+    // (start in `text`, span in `original`) of every chunk normalized on its own
+    chunks: Vec<(usize, Range<usize>)>,
+}
+
+impl<'a> NormalizedText<'a> {
+    pub fn new(original: &'a str, normalization: Normalization) -> Self {
+        let mut text = String::with_capacity(original.len());
+        let mut chunks = Vec::new();
+
+        for span in independent_chunks(original, normalization) {
+            chunks.push((text.len(), span.clone()));
+            text.push_str(&normalization.normalize(&original[span]));
+        }
+
+        Self {
+            original,
+            text,
+            chunks,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn original(&self) -> &'a str {
+        self.original
+    }
+
+    /// Span of the original text covering the given non-empty span of normalized text.
+    pub fn original_span(&self, span: Range<usize>) -> Range<usize> {
+        let chunk_at = |offset: usize| {
+            let idx = self.chunks.partition_point(|(start, _)| *start <= offset);
+            &self.chunks[idx - 1].1
+        };
+
+        chunk_at(span.start).start..chunk_at(span.end - 1).end
+    }
+}
+
+// Split text into chunks that normalize the same on their own as in context: a chunk starts
+// at every starter that never composes with what precedes it, and holds the combining marks
+// following it. Under NFKC this is decided on the first character of the compatibility
+// decomposition, e.g. the halfwidth voiced sound mark U+FF9E decomposes to the combining
+// U+3099 and composes with the kana before it.
+fn independent_chunks(
+    text: &str,
+    normalization: Normalization,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let leading = |c: char| match normalization {
+        Normalization::Nfkc => {
+            let mut first = None;
+            decompose_compatible(c, |d| {
+                first.get_or_insert(d);
+            });
+            first.unwrap_or(c)
+        }
+        Normalization::None | Normalization::Nfc => c,
+    };
+    let mut starts: Vec<usize> = text
+        .char_indices()
+        .filter(|(idx, c)| {
+            *idx == 0
+                || (canonical_combining_class(leading(*c)) == 0
+                    && is_nfc_quick(std::iter::once(leading(*c))) != IsNormalized::Maybe)
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+    starts.push(text.len());
+
+    (0..starts.len() - 1).map(move |i| starts[i]..starts[i + 1])
+}
+
+// first code point of every run of ten decimal digits (general category Nd) in Unicode 15
+const DECIMAL_DIGIT_ZEROS: [u32; 68] = [
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0,
+    0x11650, 0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x11F50, 0x16A60,
+    0x16AC0, 0x16B50, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0,
+    0x1E950, 0x1FBF0,
+];
+
+/// Value of a decimal digit of any script, e.g. '3', '３' or '٣'.
+pub fn decimal_digit_value(c: char) -> Option<u32> {
+    let code_point = u32::from(c);
+    let idx = DECIMAL_DIGIT_ZEROS.partition_point(|zero| *zero <= code_point);
+    let zero = DECIMAL_DIGIT_ZEROS[idx.checked_sub(1)?];
+
+    Some(code_point - zero).filter(|value| *value < 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case('7', Some(7))]
+    #[case('３', Some(3))]
+    #[case('٣', Some(3))]
+    #[case('۹', Some(9))]
+    #[case('९', Some(9))]
+    #[case('𝟘', Some(0))]
+    #[case('𝟿', Some(9))]
+    #[case('a', None)]
+    #[case('①', None)]
+    #[case('/', None)]
+    #[case('\u{66A}', None)]
+    fn test_decimal_digit_value(#[case] c: char, #[case] expected: Option<u32>) {
+        assert_eq!(decimal_digit_value(c), expected);
+    }
+
+    #[test]
+    fn test_decimal_digit_value_matches_ascii() {
+        for c in (0..=0x7F).filter_map(char::from_u32) {
+            assert_eq!(decimal_digit_value(c), c.to_digit(10), "{c:?}");
+        }
+    }
+
+    #[rstest]
+    #[case(Normalization::None, "e\u{301}1", "e\u{301}1")]
+    #[case(Normalization::Nfc, "e\u{301}1", "é1")]
+    #[case(Normalization::Nfc, "ｆｉｖｅ", "ｆｉｖｅ")]
+    #[case(Normalization::Nfkc, "ｆｉｖｅ", "five")]
+    #[case(Normalization::Nfkc, "ﬁve①", "five1")]
+    #[case(Normalization::Nfc, "\u{1100}\u{1161}\u{11A8}", "\u{AC01}")]
+    #[case(Normalization::Nfkc, "ｶﾞ1", "ガ1")]
+    #[case(Normalization::Nfc, "ｶﾞ", "ｶﾞ")]
+    fn test_normalized_text(
+        #[case] normalization: Normalization,
+        #[case] original: &str,
+        #[case] expected: &str,
+    ) {
+        let text = NormalizedText::new(original, normalization);
+
+        assert_eq!(text.as_str(), expected);
+        assert_eq!(text.as_str(), normalization.normalize(original));
+    }
+
+    #[rstest]
+    // "é" (0..2) comes from "e\u{301}" (0..3)
+    #[case(Normalization::Nfc, "e\u{301}1", 0..2, 0..3)]
+    #[case(Normalization::Nfc, "e\u{301}1", 2..3, 3..4)]
+    // "five" (0..4) comes from "ｆｉｖｅ" (0..12)
+    #[case(Normalization::Nfkc, "xｆｉｖｅ", 1..5, 1..13)]
+    #[case(Normalization::Nfkc, "xｆｉｖｅ", 2..3, 4..7)]
+    // "fi" comes from a single "ﬁ" ligature
+    #[case(Normalization::Nfkc, "ﬁve", 1..2, 0..3)]
+    // "ガ" comes from "ｶ" and the halfwidth voiced sound mark
+    #[case(Normalization::Nfkc, "ｶﾞ1", 0..3, 0..6)]
+    fn test_original_span(
+        #[case] normalization: Normalization,
+        #[case] original: &str,
+        #[case] span: Range<usize>,
+        #[case] expected: Range<usize>,
+    ) {
+        let text = NormalizedText::new(original, normalization);
+        assert_eq!(text.original_span(span), expected);
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use crate::cube_set::{Color, CubeSet};
+use crate::game::Game;
+
+/// How far a game is from the limits of a bag
+#[derive(Debug, PartialEq)]
+pub struct Slack {
+    pub game_id: u64,
+    /// cubes left in the bag once the game is played, per color of either the bag or the game,
+    /// negative when the game needs more cubes than the bag holds
+    pub per_color: BTreeMap<Color, i64>,
+}
+
+impl Slack {
+    pub fn new(game: &Game, bag: &CubeSet) -> Self {
+        let needed = game.min_cube_set();
+        let per_color = bag
+            .iter()
+            .chain(needed.iter())
+            .map(|(color, _)| {
+                let slack = bag.get(color) as i64 - needed.get(color) as i64;
+                (color.to_string(), slack)
+            })
+            .collect();
+
+        Self {
+            game_id: game.game_id,
+            per_color,
+        }
+    }
+
+    /// Color the bag is the most cubes short of, with the number of missing cubes,
+    /// `None` if the game is possible
+    pub fn limiting_color(&self) -> Option<(&str, u64)> {
+        self.per_color
+            .iter()
+            .filter(|(_, slack)| **slack < 0)
+            .min_by_key(|(_, slack)| **slack)
+            .map(|(color, slack)| (color.as_str(), slack.unsigned_abs()))
+    }
+}
+
+pub fn slacks(games: &[Game], bag: &CubeSet) -> Vec<Slack> {
+    games.iter().map(|game| Slack::new(game, bag)).collect()
+}
+
+/// Smallest bags under which at least `fraction` of the games are possible.
+///
+/// A bag with fewer cubes of one color but more of another is not comparable to the others,
+/// so every bag that no other one is a subset of is returned, i.e. the Pareto frontier,
+/// ordered by number of cubes.
+pub fn minimal_bags(games: &[Game], fraction: f64) -> Vec<CubeSet> {
+    let needed: Vec<CubeSet> = games.iter().map(Game::min_cube_set).collect();
+    let quota = (0..=needed.len())
+        .find(|count| *count as f64 / needed.len() as f64 >= fraction)
+        .unwrap_or(needed.len());
+
+    let mut colors: Vec<&str> = needed
+        .iter()
+        .flat_map(|cube_set| cube_set.iter().map(|(color, _)| color))
+        .collect();
+    colors.sort_unstable();
+    colors.dedup();
+
+    let mut candidates = Vec::new();
+    let eligible: Vec<&CubeSet> = needed.iter().collect();
+    candidate_bags(
+        &colors,
+        &eligible,
+        quota,
+        CubeSet::default(),
+        &mut candidates,
+    );
+
+    // a bag can only be a subset of bags with at least as many cubes
+    candidates.sort_by_key(total_cubes);
+    let mut frontier: Vec<CubeSet> = Vec::new();
+    for candidate in candidates {
+        if !frontier.iter().any(|bag| *bag <= candidate) {
+            frontier.push(candidate);
+        }
+    }
+    frontier
+}
+
+// Every color of a minimal bag holds as many cubes as one of the games needs or none, so it is enough
+// to try each of those counts for all colors but the last one, and to take just enough cubes of
+// the last color for `quota` of the games that are still possible.
+fn candidate_bags(
+    colors: &[&str],
+    eligible: &[&CubeSet],
+    quota: usize,
+    bag: CubeSet,
+    candidates: &mut Vec<CubeSet>,
+) {
+    if eligible.len() < quota {
+        return;
+    }
+
+    match colors {
+        [] => candidates.push(bag),
+        [last] => {
+            let mut counts: Vec<u64> = eligible.iter().map(|needed| needed.get(last)).collect();
+            counts.sort_unstable();
+
+            let mut bag = bag;
+            bag.set(*last, quota.checked_sub(1).map_or(0, |idx| counts[idx]));
+            candidates.push(bag);
+        }
+        [color, rest @ ..] => {
+            let mut counts: Vec<u64> = eligible.iter().map(|needed| needed.get(color)).collect();
+            counts.push(0);
+            counts.sort_unstable();
+            counts.dedup();
+
+            for count in counts {
+                let eligible: Vec<&CubeSet> = eligible
+                    .iter()
+                    .copied()
+                    .filter(|needed| needed.get(color) <= count)
+                    .collect();
+                let mut bag = bag.clone();
+                bag.set(*color, count);
+                candidate_bags(rest, &eligible, quota, bag, candidates);
+            }
+        }
+    }
+}
+
+fn total_cubes(cube_set: &CubeSet) -> u64 {
+    cube_set.iter().map(|(_, count)| count).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_set::BAG;
+    use crate::game::{example_games, games};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, &[("blue", 8), ("green", 11), ("red", 8)], None)]
+    #[case(3, &[("blue", 8), ("green", 0), ("red", -8)], Some(("red", 8)))]
+    #[case(4, &[("blue", -1), ("green", 10), ("red", -2)], Some(("red", 2)))]
+    fn test_slack(
+        #[case] game_id: u64,
+        #[case] per_color: &[(&str, i64)],
+        #[case] limiting_color: Option<(&str, u64)>,
+    ) {
+        let games = example_games();
+        let slack = Slack::new(&games[game_id as usize - 1], &BAG);
+
+        let expected: BTreeMap<Color, i64> = per_color
+            .iter()
+            .map(|(color, slack)| (color.to_string(), *slack))
+            .collect();
+        assert_eq!(slack.game_id, game_id);
+        assert_eq!(slack.per_color, expected);
+        assert_eq!(slack.limiting_color(), limiting_color);
+    }
+
+    #[test]
+    fn test_slack_with_colors_missing_from_bag() {
+        let game = Game::try_from_str("Game 1: 2 yellow, 1 red").unwrap();
+        let slack = Slack::new(&game, &CubeSet::from([(1, "red"), (1, "blue")]));
+
+        assert_eq!(slack.limiting_color(), Some(("yellow", 2)));
+        assert_eq!(slack.per_color.get("blue"), Some(&1));
+    }
+
+    #[rstest]
+    #[case(0.0, vec![CubeSet::default()])]
+    #[case(0.2, vec![
+        CubeSet::from([(1, "red"), (3, "green"), (4, "blue")]),
+        CubeSet::from([(6, "red"), (3, "green"), (2, "blue")]),
+        CubeSet::from([(4, "red"), (2, "green"), (6, "blue")]),
+    ])]
+    #[case(0.6, vec![CubeSet::from([(6, "red"), (3, "green"), (6, "blue")])])]
+    #[case(0.8, vec![
+        CubeSet::from([(14, "red"), (3, "green"), (15, "blue")]),
+        CubeSet::from([(20, "red"), (13, "green"), (6, "blue")]),
+    ])]
+    #[case(1.0, vec![CubeSet::from([(20, "red"), (13, "green"), (15, "blue")])])]
+    fn test_minimal_bags(#[case] fraction: f64, #[case] expected: Vec<CubeSet>) {
+        assert_eq!(minimal_bags(&example_games(), fraction), expected);
+    }
+
+    #[test]
+    fn test_minimal_bags_cover_fraction() {
+        let games = games(include_str!("input.txt"));
+
+        let bags = minimal_bags(&games, 0.5);
+
+        assert!(!bags.is_empty());
+        for bag in &bags {
+            let possible = games.iter().filter(|game| game.is_possible(bag)).count();
+            assert!(possible * 2 >= games.len(), "{bag}");
+            assert!(bags
+                .iter()
+                .all(|other| other == bag || other.partial_cmp(bag).is_none()));
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::cube_set::CubeSet;
+use crate::game::Game;
+
+/// Parse candidate bags, one per line written like a round, e.g. "12 red, 13 green, 14 blue".
+/// Blank lines and lines starting with `#` are skipped.
+pub fn parse_bags(contents: &str) -> Result<Vec<CubeSet>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_no, line)| {
+            CubeSet::try_from_str(line)
+                .map_err(|err| format!("line {line_no}: invalid bag {line:?}: {err}"))
+        })
+        .collect()
+}
+
+/// Games that are possible with a given bag
+#[derive(Debug, PartialEq)]
+pub struct BagReport<'b> {
+    pub bag: &'b CubeSet,
+    pub possible_game_ids: Vec<u64>,
+}
+
+impl BagReport<'_> {
+    pub fn sum(&self) -> u64 {
+        self.possible_game_ids.iter().sum()
+    }
+}
+
+pub fn evaluate_bags<'b>(games: &[Game], bags: &'b [CubeSet]) -> Vec<BagReport<'b>> {
+    bags.iter()
+        .map(|bag| BagReport {
+            bag,
+            possible_game_ids: games
+                .iter()
+                .filter(|game| game.is_possible(bag))
+                .map(|game| game.game_id)
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::example_games;
+
+    #[test]
+    fn test_parse_bags() {
+        let contents = "\
+# puzzle bag
+12 red, 13 green, 14 blue
+
+  20 red, 13 green, 15 blue
+";
+
+        assert_eq!(
+            parse_bags(contents),
+            Ok(vec![
+                CubeSet::from([(12, "red"), (13, "green"), (14, "blue")]),
+                CubeSet::from([(20, "red"), (13, "green"), (15, "blue")]),
+            ])
+        );
+        assert_eq!(
+            parse_bags("12 red\n13 green;").unwrap_err(),
+            "line 2: invalid bag \"13 green;\": error Eof at: ;"
+        );
+    }
+
+    #[test]
+    fn test_evaluate_bags() {
+        let games = example_games();
+        let bags =
+            parse_bags("12 red, 13 green, 14 blue\n20 red, 13 green, 15 blue\n1 red").unwrap();
+
+        let reports = evaluate_bags(&games, &bags);
+
+        let summary: Vec<_> = reports
+            .iter()
+            .map(|report| (report.possible_game_ids.clone(), report.sum()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(vec![1, 2, 5], 8), (vec![1, 2, 3, 4, 5], 15), (vec![], 0)]
+        );
+    }
+}
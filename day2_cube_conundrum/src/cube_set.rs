@@ -1,39 +1,322 @@
-#[derive(Debug, PartialEq, Clone)]
-pub enum Color {
-    Red,
-    Green,
-    Blue,
-}
+use nom::{combinator::all_consuming, error, Finish};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Sub};
+use std::sync::LazyLock;
+
+/// Name of a cube color, e.g. "red"
+pub type Color = String;
 
-#[derive(Default, Debug, Clone)]
+/// Count of cubes per color, colors without any cube are not stored
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct CubeSet {
-    pub red: u64,
-    pub green: u64,
-    pub blue: u64,
+    counts: BTreeMap<Color, u64>,
 }
 
 impl From<Vec<(u64, Color)>> for CubeSet {
     fn from(count_color_pairs: Vec<(u64, Color)>) -> Self {
         let mut cube_set = CubeSet::default();
         for (count, color) in count_color_pairs {
-            match color {
-                Color::Red => cube_set.red += count,
-                Color::Green => cube_set.green += count,
-                Color::Blue => cube_set.blue += count,
-            }
+            let total = cube_set.get(&color) + count;
+            cube_set.set(color, total);
         }
         cube_set
     }
 }
 
+impl<const N: usize> From<[(u64, &str); N]> for CubeSet {
+    fn from(count_color_pairs: [(u64, &str); N]) -> Self {
+        count_color_pairs
+            .map(|(count, color)| (count, color.to_string()))
+            .to_vec()
+            .into()
+    }
+}
+
 impl CubeSet {
+    /// Parse cubes written the same way as a round, e.g. "12 red, 13 green, 14 blue"
+    pub fn try_from_str(input: &str) -> Result<CubeSet, error::Error<&str>> {
+        let (_, cube_set) = all_consuming(parser::cube_set)(input).finish()?;
+        Ok(cube_set)
+    }
+
+    pub fn get(&self, color: &str) -> u64 {
+        self.counts.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, color: impl Into<Color>, count: u64) {
+        let color = color.into();
+        if count == 0 {
+            self.counts.remove(&color);
+        } else {
+            self.counts.insert(color, count);
+        }
+    }
+
+    /// Colors with at least one cube, with their count, sorted by color
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.counts
+            .iter()
+            .map(|(color, count)| (color.as_str(), *count))
+    }
+
+    /// Product of the counts of the colors of the puzzle, 0 if any of them is missing
     pub fn power(&self) -> u64 {
-        self.red * self.green * self.blue
+        self.power_over(&PALETTE)
+    }
+
+    /// Product of the counts of the colors of `palette`, colors missing from the set
+    /// counting as 0
+    pub fn power_over(&self, palette: &[&str]) -> u64 {
+        palette.iter().map(|color| self.get(color)).product()
+    }
+
+    /// Whether every color has at most as many cubes as in `other`
+    pub fn is_subset_of(&self, other: &CubeSet) -> bool {
+        self.iter().all(|(color, count)| count <= other.get(color))
+    }
+
+    /// Largest count of every color in either set
+    pub fn join(&self, other: &CubeSet) -> CubeSet {
+        self.combine(other, |a, b| Some(a.max(b)))
+            .expect("max of two counts always exists")
+    }
+
+    /// Smallest count of every color in both sets
+    pub fn meet(&self, other: &CubeSet) -> CubeSet {
+        self.combine(other, |a, b| Some(a.min(b)))
+            .expect("min of two counts always exists")
+    }
+
+    /// Cubes of both sets, `None` if a count overflows
+    pub fn checked_add(&self, other: &CubeSet) -> Option<CubeSet> {
+        self.combine(other, u64::checked_add)
+    }
+
+    /// Cubes left after taking out `other`, `None` if it is not a subset of `self`
+    pub fn checked_sub(&self, other: &CubeSet) -> Option<CubeSet> {
+        self.combine(other, u64::checked_sub)
+    }
+
+    // applies `op` to the counts of every color present in either set
+    fn combine(&self, other: &CubeSet, op: impl Fn(u64, u64) -> Option<u64>) -> Option<CubeSet> {
+        let colors: BTreeSet<&str> = self
+            .counts
+            .keys()
+            .chain(other.counts.keys())
+            .map(String::as_str)
+            .collect();
+
+        let mut cube_set = CubeSet::default();
+        for color in colors {
+            cube_set.set(color, op(self.get(color), other.get(color))?);
+        }
+        Some(cube_set)
+    }
+}
+
+/// Sets are ordered by dominance: a set is smaller than another when it is a strict subset of it,
+/// sets with more cubes of one color and fewer of another are not comparable.
+impl PartialOrd for CubeSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.is_subset_of(other), other.is_subset_of(self)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+impl Add<&CubeSet> for &CubeSet {
+    type Output = CubeSet;
+
+    fn add(self, rhs: &CubeSet) -> CubeSet {
+        self.checked_add(rhs).expect("attempt to add with overflow")
+    }
+}
+
+impl Add for CubeSet {
+    type Output = CubeSet;
+
+    fn add(self, rhs: CubeSet) -> CubeSet {
+        &self + &rhs
+    }
+}
+
+impl Sub<&CubeSet> for &CubeSet {
+    type Output = CubeSet;
+
+    fn sub(self, rhs: &CubeSet) -> CubeSet {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract cubes missing from the set")
+    }
+}
+
+impl Sub for CubeSet {
+    type Output = CubeSet;
+
+    fn sub(self, rhs: CubeSet) -> CubeSet {
+        &self - &rhs
+    }
+}
+
+impl Sum for CubeSet {
+    fn sum<I: Iterator<Item = CubeSet>>(iter: I) -> Self {
+        iter.fold(CubeSet::default(), |acc, curr| acc + curr)
+    }
+}
+
+impl<'a> Sum<&'a CubeSet> for CubeSet {
+    fn sum<I: Iterator<Item = &'a CubeSet>>(iter: I) -> Self {
+        iter.fold(CubeSet::default(), |acc, curr| &acc + curr)
+    }
+}
+
+impl fmt::Display for CubeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (color, count)) in self.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{count} {color}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Colors of the puzzle
+pub const PALETTE: [&str; 3] = ["red", "green", "blue"];
+
+pub static BAG: LazyLock<CubeSet> =
+    LazyLock::new(|| CubeSet::from([(12, "red"), (13, "green"), (14, "blue")]));
+
+pub(crate) mod parser {
+    use super::*;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{alpha1, space0, u64},
+        combinator::map,
+        multi::separated_list1,
+        sequence::{pair, preceded},
+        IResult, Parser,
+    };
+
+    pub fn cube_set(input: &str) -> IResult<&str, CubeSet> {
+        map(count_color_pairs, CubeSet::from)(input)
+    }
+
+    /// Counts as written, before duplicate colors are summed and zero counts dropped
+    pub fn count_color_pairs(input: &str) -> IResult<&str, Vec<(u64, Color)>> {
+        separated_list1(
+            tag(",").and(space0),
+            pair(u64, preceded(space0, map(alpha1, Color::from))),
+        )(input)
     }
 }
 
-pub const BAG: CubeSet = CubeSet {
-    red: 12,
-    green: 13,
-    blue: 14,
-};
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("12 red, 13 green, 14 blue", Some(CubeSet::from([(12, "red"), (13, "green"), (14, "blue")])))]
+    #[case("3 red,4 yellow, 1 red", Some(CubeSet::from([(4, "red"), (4, "yellow")])))]
+    #[case("12 red, 13", None)]
+    #[case("12 red; 13 green", None)]
+    #[case("", None)]
+    fn test_try_from_str(#[case] input: &str, #[case] expected: Option<CubeSet>) {
+        assert_eq!(CubeSet::try_from_str(input).ok(), expected);
+    }
+
+    #[rstest]
+    #[case("1 red, 2 blue", "1 red, 2 blue", Some(Ordering::Equal))]
+    #[case("1 red", "1 red, 2 blue", Some(Ordering::Less))]
+    #[case("1 red, 2 blue", "2 red, 2 blue", Some(Ordering::Less))]
+    #[case("3 red, 2 blue", "2 red, 2 blue", Some(Ordering::Greater))]
+    #[case("3 red", "2 red, 2 blue", None)]
+    #[case("1 yellow", "12 red, 13 green, 14 blue", None)]
+    fn test_partial_cmp(#[case] a: &str, #[case] b: &str, #[case] expected: Option<Ordering>) {
+        let (a, b) = (
+            CubeSet::try_from_str(a).unwrap(),
+            CubeSet::try_from_str(b).unwrap(),
+        );
+
+        assert_eq!(a.partial_cmp(&b), expected);
+        assert_eq!(b.partial_cmp(&a), expected.map(Ordering::reverse));
+        assert_eq!(a.is_subset_of(&b), a <= b);
+    }
+
+    #[rstest]
+    #[case("4 red, 2 green, 6 blue", &PALETTE, 48)]
+    // a missing color of the palette makes the power 0
+    #[case("4 red, 6 blue", &PALETTE, 0)]
+    #[case("4 red, 6 blue, 3 yellow", &PALETTE, 0)]
+    #[case("4 red, 6 blue, 3 yellow", &["red", "blue", "yellow"], 72)]
+    #[case("4 red, 6 blue", &["red", "yellow"], 0)]
+    #[case("4 red", &[], 1)]
+    fn test_power_over(#[case] input: &str, #[case] palette: &[&str], #[case] expected: u64) {
+        let cubes = CubeSet::try_from_str(input).unwrap();
+        assert_eq!(cubes.power_over(palette), expected);
+    }
+
+    #[test]
+    fn test_power() {
+        assert_eq!(BAG.power(), 12 * 13 * 14);
+        assert_eq!(CubeSet::default().power(), 0);
+        assert_eq!(CubeSet::from([(2, "red"), (3, "blue")]).power(), 0);
+    }
+
+    #[test]
+    fn test_join_meet() {
+        let a = CubeSet::from([(3, "red"), (1, "blue")]);
+        let b = CubeSet::from([(1, "red"), (4, "green")]);
+
+        assert_eq!(
+            a.join(&b),
+            CubeSet::from([(3, "red"), (4, "green"), (1, "blue")])
+        );
+        assert_eq!(a.meet(&b), CubeSet::from([(1, "red")]));
+        assert!(a.meet(&b) <= a && a <= a.join(&b));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = CubeSet::from([(3, "red"), (1, "blue")]);
+        let b = CubeSet::from([(1, "red"), (4, "green")]);
+
+        assert_eq!(
+            &a + &b,
+            CubeSet::from([(4, "red"), (4, "green"), (1, "blue")])
+        );
+        assert_eq!(&(&a + &b) - &b, a);
+        assert_eq!(
+            a.checked_sub(&CubeSet::from([(3, "red")])),
+            Some(CubeSet::from([(1, "blue")]))
+        );
+        assert_eq!(a.checked_sub(&b), None);
+        assert_eq!(a.checked_add(&CubeSet::from([(u64::MAX, "red")])), None);
+        assert_eq!([a.clone(), b.clone()].iter().sum::<CubeSet>(), a + b);
+        assert_eq!(
+            Vec::<CubeSet>::new().into_iter().sum::<CubeSet>(),
+            CubeSet::default()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to subtract cubes missing from the set")]
+    fn test_sub_missing_cubes() {
+        let _ = CubeSet::from([(1, "red")]) - CubeSet::from([(1, "blue")]);
+    }
+
+    #[test]
+    fn test_display() {
+        let cube_set = CubeSet::from([(12, "red"), (13, "green"), (0, "yellow"), (14, "blue")]);
+
+        assert_eq!(cube_set.to_string(), "14 blue, 13 green, 12 red");
+        assert_eq!(CubeSet::try_from_str(&cube_set.to_string()), Ok(cube_set));
+    }
+}
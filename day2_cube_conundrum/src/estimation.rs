@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::cube_set::{Color, CubeSet};
+use crate::game::Game;
+
+/// Largest count of a color the estimator considers by default
+pub const DEFAULT_MAX_COUNT: u64 = 1_000;
+
+/// Largest count of a color the estimator can consider, so that bag sizes fit in a `u64`
+pub const LARGEST_MAX_COUNT: u64 = 1 << 62;
+
+// twice the drop in log-likelihood at the edge of a 95% interval, the 95% quantile of a
+// chi-squared distribution with one degree of freedom
+const CHI_SQUARED_95: f64 = 3.841;
+
+/// Maximum-likelihood estimate of the bag contents from observed games.
+///
+/// Every round is a draw without replacement from the full bag, so the probability of a round
+/// follows a multivariate hypergeometric distribution, and rounds are independent of each other.
+#[derive(Debug, Clone)]
+pub struct BagEstimator {
+    // per color, number of rounds with more than `i` cubes of the color at index `i`
+    colors: BTreeMap<Color, Vec<u64>>,
+    // number of rounds with more than `i` cubes at index `i`
+    sizes: Vec<u64>,
+    // part of the log-likelihood that does not depend on the bag
+    constant: f64,
+    max_count: u64,
+}
+
+/// Most likely bag with a 95% likelihood-ratio interval for every color
+#[derive(Debug, Clone, PartialEq)]
+pub struct BagEstimate {
+    pub bag: CubeSet,
+    /// lowest and highest count of every color, from its profile likelihood
+    pub intervals: BTreeMap<Color, (u64, u64)>,
+    pub log_likelihood: f64,
+    /// colors whose count stopped at the largest count considered, the likelihood possibly
+    /// still growing with more cubes
+    pub capped: BTreeSet<Color>,
+}
+
+impl BagEstimator {
+    pub fn new(games: &[Game]) -> Self {
+        let mut colors: BTreeMap<Color, Vec<u64>> = BTreeMap::new();
+        let mut sizes = Vec::new();
+        let mut constant = 0.0;
+
+        // ln P(round) = sum of ln C(K, k) over the colors - ln C(N, n), with the bag holding
+        // K cubes of a color out of N and the round k out of n, and
+        // ln C(K, k) = ln(K (K - 1) ... (K - k + 1)) - ln k!
+        for round in games.iter().flat_map(|game| &game.rounds) {
+            let size = round.iter().map(|(_, count)| count).sum();
+            for (color, count) in round.iter() {
+                add_tail_counts(colors.entry(color.to_string()).or_default(), count);
+                constant -= ln_factorial(count);
+            }
+            add_tail_counts(&mut sizes, size);
+            constant += ln_factorial(size);
+        }
+
+        Self {
+            colors,
+            sizes,
+            constant,
+            max_count: DEFAULT_MAX_COUNT,
+        }
+    }
+
+    /// Largest count of a color to consider, since the likelihood can keep growing with the bag
+    ///
+    /// # Panics
+    ///
+    /// If `max_count` is above [`LARGEST_MAX_COUNT`].
+    pub fn max_count(self, max_count: u64) -> Self {
+        assert!(
+            max_count <= LARGEST_MAX_COUNT,
+            "cannot consider more than {LARGEST_MAX_COUNT} cubes of a color"
+        );
+        Self { max_count, ..self }
+    }
+
+    /// Log of the probability of the observed rounds with the given bag,
+    /// `-inf` if the bag cannot produce them.
+    pub fn log_likelihood(&self, bag: &CubeSet) -> f64 {
+        let total: u64 = bag.iter().map(|(_, count)| count).sum();
+        let too_small = total < self.sizes.len() as u64
+            || (self.colors.iter())
+                .any(|(color, tail_counts)| bag.get(color) < tail_counts.len() as u64);
+        if too_small {
+            return f64::NEG_INFINITY;
+        }
+
+        let colors: f64 = self
+            .colors
+            .iter()
+            .map(|(color, tail_counts)| ln_falling_factorials(bag.get(color), tail_counts))
+            .sum();
+        self.constant + colors - ln_falling_factorials(total, &self.sizes)
+    }
+
+    pub fn estimate(&self) -> BagEstimate {
+        // start from the smallest bag for the observed rounds, which is always possible
+        let mut bag = CubeSet::default();
+        for color in self.colors.keys() {
+            bag.set(color.as_str(), self.lowest(color));
+        }
+        let (bag, log_likelihood) = self.climb(bag, None);
+
+        let intervals = self
+            .colors
+            .keys()
+            .map(|color| (color.clone(), self.interval(&bag, color, log_likelihood)))
+            .collect();
+
+        let capped = (bag.iter())
+            .filter(|(_, count)| *count == self.max_count)
+            .map(|(color, _)| color.to_string())
+            .collect();
+
+        BagEstimate {
+            bag,
+            intervals,
+            log_likelihood,
+            capped,
+        }
+    }
+
+    // hill climbing from `bag` with steps going down to a single cube, leaving the count
+    // of the `fixed` color as it is
+    fn climb(&self, mut bag: CubeSet, fixed: Option<&str>) -> (CubeSet, f64) {
+        let mut log_likelihood = self.log_likelihood(&bag);
+
+        let mut step = self.max_count.next_power_of_two();
+        while step > 0 {
+            loop {
+                let better = self
+                    .neighbours(&bag, step, fixed)
+                    .into_iter()
+                    .map(|next| {
+                        let next_log_likelihood = self.log_likelihood(&next);
+                        (next, next_log_likelihood)
+                    })
+                    .find(|(_, next_log_likelihood)| *next_log_likelihood > log_likelihood);
+                let Some(better) = better else {
+                    break;
+                };
+                (bag, log_likelihood) = better;
+            }
+            step /= 2;
+        }
+
+        (bag, log_likelihood)
+    }
+
+    // bags with `step` more or fewer cubes of one color, or of every color at once since
+    // the likelihood can grow with the bag as a whole but not with any single color
+    fn neighbours(&self, bag: &CubeSet, step: u64, fixed: Option<&str>) -> Vec<CubeSet> {
+        let free_colors: Vec<&str> = (self.colors.keys().map(String::as_str))
+            .filter(|color| Some(*color) != fixed)
+            .collect();
+        let moves = (free_colors.iter().map(|color| vec![*color])).chain([free_colors.clone()]);
+
+        let mut neighbours = Vec::new();
+        for colors in moves {
+            for op in [u64::checked_add, u64::checked_sub] {
+                let next = colors.iter().try_fold(bag.clone(), |mut next, color| {
+                    let count = op(bag.get(color), step)
+                        .filter(|count| (self.lowest(color)..=self.max_count).contains(count))?;
+                    next.set(*color, count);
+                    Some(next)
+                });
+                neighbours.extend(next);
+            }
+        }
+        neighbours
+    }
+
+    // fewest cubes of a color the observed rounds can be drawn with
+    fn lowest(&self, color: &str) -> u64 {
+        self.colors
+            .get(color)
+            .map_or(0, |tail_counts| tail_counts.len() as u64)
+    }
+
+    // counts of `color` whose profile likelihood, the highest likelihood of a bag with that
+    // count, is within the 95% threshold of the maximum
+    fn interval(&self, bag: &CubeSet, color: &str, max: f64) -> (u64, u64) {
+        let within = |count: u64| {
+            let mut bag = bag.clone();
+            bag.set(color, count);
+            let (_, log_likelihood) = self.climb(bag, Some(color));
+            2.0 * (max - log_likelihood) <= CHI_SQUARED_95
+        };
+
+        // the counts within the threshold form a single range around the estimate
+        let estimate = bag.get(color);
+        let bound = |limit: u64| {
+            if within(limit) {
+                return limit;
+            }
+            let (mut inside, mut outside) = (estimate, limit);
+            while inside.abs_diff(outside) > 1 {
+                let mid = inside.midpoint(outside);
+                if within(mid) {
+                    inside = mid;
+                } else {
+                    outside = mid;
+                }
+            }
+            inside
+        };
+
+        (bound(self.lowest(color)), bound(self.max_count))
+    }
+}
+
+// counts one more value for every index below `count`
+fn add_tail_counts(tail_counts: &mut Vec<u64>, count: u64) {
+    let count = count as usize;
+    if tail_counts.len() < count {
+        tail_counts.resize(count, 0);
+    }
+    for tail_count in &mut tail_counts[..count] {
+        *tail_count += 1;
+    }
+}
+
+fn ln_factorial(n: u64) -> f64 {
+    (2..=n).map(|i| (i as f64).ln()).sum()
+}
+
+// sum of ln(n (n - 1) ... (n - k + 1)) over the rounds, `tail_counts[i]` being the number
+// of rounds with `k > i`
+fn ln_falling_factorials(n: u64, tail_counts: &[u64]) -> f64 {
+    tail_counts
+        .iter()
+        .enumerate()
+        .map(|(i, rounds)| *rounds as f64 * (n as f64 - i as f64).ln())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_set::BAG;
+    use crate::game::games;
+    use crate::simulation::DrawSimulator;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_log_likelihood() {
+        let estimator = BagEstimator::new(&games("Game 1: 1 red; 1 red, 1 blue"));
+
+        // 1/2 to draw the red cube, then 1 as both cubes are drawn
+        let bag = CubeSet::from([(1, "red"), (1, "blue")]);
+        assert!((estimator.log_likelihood(&bag) - 0.5f64.ln()).abs() < 1e-9);
+        assert_eq!(
+            estimator.log_likelihood(&CubeSet::from([(1, "red")])),
+            f64::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn test_whole_bag_drawn() {
+        let estimator = BagEstimator::new(&games("Game 1: 2 red, 3 blue")).max_count(100);
+
+        let estimate = estimator.estimate();
+
+        assert_eq!(estimate.bag, CubeSet::from([(2, "red"), (3, "blue")]));
+        // a single round says little about the size of the bag
+        assert_eq!(estimate.intervals["red"], (2, 100));
+        assert!(estimate.log_likelihood.abs() < 1e-9);
+        assert!(estimate.capped.is_empty());
+    }
+
+    #[test]
+    fn test_max_count() {
+        // drawing two cubes of the same color gets more likely as the bag grows
+        let estimator = BagEstimator::new(&games("Game 1: 2 red; 2 blue")).max_count(50);
+
+        let estimate = estimator.estimate();
+
+        assert_eq!(estimate.bag, CubeSet::from([(50, "red"), (50, "blue")]));
+        assert_eq!(estimate.intervals["red"].1, 50);
+        assert_eq!(
+            estimate.capped,
+            BTreeSet::from(["blue".to_string(), "red".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_largest_max_count() {
+        let estimator =
+            BagEstimator::new(&games("Game 1: 2 red, 3 blue")).max_count(LARGEST_MAX_COUNT);
+
+        let estimate = estimator.estimate();
+
+        assert_eq!(estimate.bag, CubeSet::from([(2, "red"), (3, "blue")]));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot consider more than 4611686018427387904 cubes of a color")]
+    fn test_max_count_too_large() {
+        let _ = BagEstimator::new(&games("Game 1: 2 red, 3 blue")).max_count(LARGEST_MAX_COUNT + 1);
+    }
+
+    #[test]
+    fn test_recovers_simulated_bag() {
+        let mut simulator = DrawSimulator::new(&BAG, StdRng::seed_from_u64(2023));
+        let games: Vec<Game> = (1..=200)
+            .map(|game_id| simulator.random_game(game_id, 3..=6, 10..=30))
+            .collect();
+
+        let estimate = BagEstimator::new(&games).estimate();
+
+        for (color, count) in BAG.iter() {
+            let (low, high) = estimate.intervals[color];
+            assert!((low..=high).contains(&count), "{color}: {low}..={high}");
+            assert!(
+                estimate.bag.get(color).abs_diff(count) <= 2,
+                "{}",
+                estimate.bag
+            );
+        }
+        assert!(estimate.log_likelihood >= BagEstimator::new(&games).log_likelihood(&BAG));
+        assert!(estimate.capped.is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use nom::{error, Finish};
+use std::fmt;
 
 use crate::cube_set::CubeSet;
-use std::cmp::max;
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -25,32 +25,54 @@ impl Game {
     }
 
     pub fn is_possible(&self, bag: &CubeSet) -> bool {
-        self.rounds
-            .iter()
-            .all(|round| round.red <= bag.red && round.green <= bag.green && round.blue <= bag.blue)
+        self.rounds.iter().all(|round| round.is_subset_of(bag))
     }
 
     pub fn min_cube_set(&self) -> CubeSet {
         self.rounds
             .iter()
-            .fold(CubeSet::default(), |acc, curr| CubeSet {
-                red: max(acc.red, curr.red),
-                green: max(acc.green, curr.green),
-                blue: max(acc.blue, curr.blue),
-            })
+            .fold(CubeSet::default(), |acc, curr| acc.join(curr))
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}: ", self.game_id)?;
+        for (idx, round) in self.rounds.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{round}")?;
+        }
+        Ok(())
     }
 }
 
-mod parser {
+/// Game of every line of `input`, panicking on an invalid one
+#[cfg(test)]
+pub(crate) fn games(input: &str) -> Vec<Game> {
+    input
+        .lines()
+        .map(|line| Game::try_from_str(line).unwrap())
+        .collect()
+}
+
+/// Games of the example of the puzzle
+#[cfg(test)]
+pub(crate) fn example_games() -> Vec<Game> {
+    games(include_str!("example.txt"))
+}
+
+pub(crate) mod parser {
     use super::*;
+    use crate::cube_set::parser::{count_color_pairs, cube_set};
     use crate::cube_set::Color;
     use nom::{
-        branch::alt,
         bytes::complete::tag,
         character::complete::{space0, u64},
-        combinator::{map, value},
+        combinator::map,
         multi::separated_list1,
-        sequence::{delimited, pair, preceded, tuple},
+        sequence::{delimited, tuple},
         IResult, Parser,
     };
 
@@ -58,31 +80,22 @@ mod parser {
         map(tuple((game_id, rounds)), Game::from)(input)
     }
 
-    fn game_id(input: &str) -> IResult<&str, u64> {
-        delimited(tag("Game "), u64, tag(": "))(input)
+    /// Game ID and rounds as written, see [`count_color_pairs`]
+    pub type RawGame = (u64, Vec<Vec<(u64, Color)>>);
+
+    pub fn raw_game(input: &str) -> IResult<&str, RawGame> {
+        tuple((
+            game_id,
+            separated_list1(tag(";").and(space0), count_color_pairs),
+        ))(input)
     }
 
-    fn round(input: &str) -> IResult<&str, CubeSet> {
-        let (rem, out) = separated_list1(
-            tag(",").and(space0),
-            pair(
-                u64,
-                preceded(
-                    space0,
-                    alt((
-                        value(Color::Red, tag("red")),
-                        value(Color::Green, tag("green")),
-                        value(Color::Blue, tag("blue")),
-                    )),
-                ),
-            ),
-        )(input)?;
-
-        Ok((rem, CubeSet::from(out)))
+    fn game_id(input: &str) -> IResult<&str, u64> {
+        delimited(tag("Game "), u64, tag(": "))(input)
     }
 
     fn rounds(input: &str) -> IResult<&str, Vec<CubeSet>> {
-        separated_list1(tag(";").and(space0), round)(input)
+        separated_list1(tag(";").and(space0), cube_set)(input)
     }
 }
 
@@ -120,4 +133,59 @@ mod tests {
         assert_eq!(game.is_possible(&BAG), possible);
         assert_eq!(game.min_cube_set().power(), min_cube_set_power);
     }
+
+    #[rstest]
+    #[case("Game 6: 4 yellow, 2 red; 1 yellow, 3 red", true, false, 12, 0)]
+    #[case(
+        "Game 7: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+        true,
+        true,
+        48,
+        48
+    )]
+    #[case("Game 8: 3 red; 2 purple, 1 red", true, false, 6, 0)]
+    #[case("Game 9: 3 red; 3 purple, 1 red", false, false, 9, 0)]
+    fn test_game_with_any_colors(
+        #[case] input: &str,
+        #[case] possible: bool,
+        #[case] possible_with_default_bag: bool,
+        #[case] min_cube_set_power_over_colors: u64,
+        #[case] min_cube_set_power: u64,
+    ) {
+        let bag = CubeSet::from([
+            (4, "yellow"),
+            (12, "red"),
+            (13, "green"),
+            (14, "blue"),
+            (2, "purple"),
+        ]);
+        let game = Game::try_from_str(input).unwrap();
+
+        assert_eq!(game.is_possible(&bag), possible);
+        assert_eq!(game.is_possible(&BAG), possible_with_default_bag);
+        // the power over the colors of the game, and over the colors of the puzzle
+        let min_cube_set = game.min_cube_set();
+        let colors: Vec<&str> = min_cube_set.iter().map(|(color, _)| color).collect();
+        assert_eq!(
+            min_cube_set.power_over(&colors),
+            min_cube_set_power_over_colors
+        );
+        assert_eq!(min_cube_set.power(), min_cube_set_power);
+    }
+
+    #[test]
+    fn test_min_cube_set() {
+        let game = Game::try_from_str("Game 1: 4 yellow, 2 red; 1 yellow, 3 red, 1 red").unwrap();
+        assert_eq!(
+            game.min_cube_set(),
+            CubeSet::from([(4, "yellow"), (4, "red")])
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let game = Game::try_from_str("Game 7: 4 yellow, 2 red; 1 yellow, 3 red, 1 red").unwrap();
+
+        assert_eq!(game.to_string(), "Game 7: 2 red, 4 yellow; 4 red, 1 yellow");
+    }
 }
@@ -1,27 +1,327 @@
-use cube_set::BAG;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use analysis::{minimal_bags, slacks};
+use bags::{evaluate_bags, parse_bags};
+use clap::builder::RangedU64ValueParser;
+use clap::{value_parser, Parser, Subcommand, ValueEnum};
+use cube_set::{CubeSet, BAG};
+use estimation::{BagEstimator, DEFAULT_MAX_COUNT, LARGEST_MAX_COUNT};
 use game::Game;
+use query::Query;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use report::Report;
+use simulation::DrawSimulator;
+use validation::{parse_games, ParseMode};
 
+mod analysis;
+mod bags;
 mod cube_set;
+mod estimation;
 mod game;
+mod query;
+mod report;
+mod simulation;
+mod validation;
+
+#[derive(Parser)]
+#[command(about = "Day 2: Cube Conundrum")]
+struct Cli {
+    /// Puzzle input, the bundled input when omitted
+    #[arg(long, global = true)]
+    input: Option<PathBuf>,
+
+    /// Bag contents written like a round, e.g. "12 red, 13 green, 14 blue", can be repeated
+    #[arg(long = "bag", global = true, value_parser = parse_bag)]
+    bags: Vec<CubeSet>,
+
+    /// File with one bag per line, blank lines and lines starting with '#' are skipped
+    #[arg(long, global = true)]
+    bag_file: Option<PathBuf>,
+
+    /// Reject duplicate colors in a round, zero counts, games without cubes
+    /// and game IDs that do not count up from 1, reporting every violation
+    #[arg(long, global = true)]
+    strict: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve both parts, part 1 against a single bag (default)
+    Solve,
+    /// Report the possible games and their ID sum for every candidate bag
+    Bags,
+    /// Show how many cubes of each color every game leaves in a single bag
+    Slack,
+    /// Find the smallest bags under which a fraction of the games are possible
+    MinimalBags {
+        /// Fraction of the games that must be possible, between 0 and 1
+        #[arg(long, default_value_t = 1.0, value_parser = parse_fraction)]
+        fraction: f64,
+    },
+    /// Print the games matching a query, e.g. "any(red > 10 and blue < 3) or power > 1000"
+    Filter {
+        /// Comparisons of `id`, `rounds`, `power`, `total` or a color count, combined with
+        /// `and`, `or`, `not`, and `any(...)` or `all(...)` to look at the rounds
+        #[arg(value_parser = parse_query)]
+        query: Query,
+
+        /// Print a single aggregate of the matching games instead of the games
+        #[arg(long, value_enum)]
+        aggregate: Option<Aggregate>,
+    },
+    /// Generate random games played with a single bag
+    Simulate {
+        /// Number of games
+        #[arg(long, default_value_t = 100)]
+        games: u64,
+
+        /// Largest number of rounds of a game
+        #[arg(long, default_value_t = 6)]
+        max_rounds: usize,
+
+        /// Largest number of cubes drawn in a round
+        #[arg(long, default_value_t = 20)]
+        max_round_size: usize,
+
+        /// Seed of the random draws, a random one when omitted
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Estimate the probability that each game was played with a single bag
+    Likelihood {
+        /// Number of simulated draws per round
+        #[arg(long, default_value_t = 10_000, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        samples: usize,
+
+        /// Seed of the random draws, a random one when omitted
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Estimate the most likely bag from the games and compare it with a single assumed bag
+    Estimate {
+        /// Largest count of a color to consider
+        #[arg(long, default_value_t = DEFAULT_MAX_COUNT, value_parser = value_parser!(u64).range(1..=LARGEST_MAX_COUNT))]
+        max_count: u64,
+    },
+    /// Report statistics per color and on the powers of the games
+    Stats {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Format {
+    Table,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Aggregate {
+    /// Number of games
+    Count,
+    /// Sum of the game IDs
+    IdSum,
+    /// Sum of the powers of the minimum cube sets
+    PowerSum,
+}
+
+impl Cli {
+    fn input(&self) -> Result<String, Box<dyn Error>> {
+        match &self.input {
+            Some(path) => Ok(fs::read_to_string(path)?),
+            None => Ok(include_str!("input.txt").to_string()),
+        }
+    }
+
+    fn games(&self) -> Result<Vec<Game>, Box<dyn Error>> {
+        let mode = if self.strict {
+            ParseMode::Strict
+        } else {
+            ParseMode::Lenient
+        };
 
-fn games() -> impl Iterator<Item = Game> {
-    include_str!("input.txt")
-        .lines()
-        .map(|line| Game::try_from_str(line).unwrap())
+        parse_games(&self.input()?, mode).map_err(|violations| {
+            for violation in &violations {
+                eprintln!("{violation}");
+            }
+            format!("{} problems found in the input", violations.len()).into()
+        })
+    }
+
+    /// Bags given on the command line followed by the ones from the bag file,
+    /// the puzzle bag if there are none.
+    fn bags(&self) -> Result<Vec<CubeSet>, Box<dyn Error>> {
+        let mut bags = self.bags.clone();
+        if let Some(path) = &self.bag_file {
+            bags.extend(parse_bags(&fs::read_to_string(path)?)?);
+        }
+        if bags.is_empty() {
+            bags.push(BAG.clone());
+        }
+        Ok(bags)
+    }
 }
 
-fn solve_part1(games: impl Iterator<Item = Game>) -> u64 {
+fn parse_bag(input: &str) -> Result<CubeSet, String> {
+    CubeSet::try_from_str(input).map_err(|err| err.to_string())
+}
+
+fn parse_fraction(input: &str) -> Result<f64, String> {
+    let fraction: f64 = input.parse().map_err(|err| format!("{err}"))?;
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(fraction)
+    } else {
+        Err(format!("{fraction} is not between 0 and 1"))
+    }
+}
+
+fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+fn parse_query(input: &str) -> Result<Query, String> {
+    Query::try_from_str(input).map_err(|err| err.to_string())
+}
+
+fn single_bag(bags: &[CubeSet]) -> Result<&CubeSet, Box<dyn Error>> {
+    match bags {
+        [bag] => Ok(bag),
+        _ => Err("this command takes a single bag, use the `bags` command for several".into()),
+    }
+}
+
+fn solve_part1(games: &[Game], bag: &CubeSet) -> u64 {
     games
-        .filter(|game| game.is_possible(&BAG))
+        .iter()
+        .filter(|game| game.is_possible(bag))
         .map(|game| game.game_id)
         .sum()
 }
 
-fn solve_part2(games: impl Iterator<Item = Game>) -> u64 {
-    games.map(|game| game.min_cube_set().power()).sum()
+fn solve_part2(games: &[Game]) -> u64 {
+    games.iter().map(|game| game.min_cube_set().power()).sum()
 }
 
-fn main() {
-    println!("part 1: {}", solve_part1(games()));
-    println!("part 2: {}", solve_part2(games()));
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let games = cli.games()?;
+    let bags = cli.bags()?;
+
+    match cli.command.unwrap_or(Command::Solve) {
+        Command::Solve => {
+            let bag = single_bag(&bags)?;
+            println!("part 1: {}", solve_part1(&games, bag));
+            println!("part 2: {}", solve_part2(&games));
+        }
+        Command::Bags => {
+            for report in evaluate_bags(&games, &bags) {
+                println!(
+                    "{}: {} possible games, sum {}, ids {:?}",
+                    report.bag,
+                    report.possible_game_ids.len(),
+                    report.sum(),
+                    report.possible_game_ids
+                );
+            }
+        }
+        Command::Slack => {
+            for slack in slacks(&games, single_bag(&bags)?) {
+                let per_color: Vec<String> = slack
+                    .per_color
+                    .iter()
+                    .map(|(color, slack)| format!("{slack} {color}"))
+                    .collect();
+                print!("Game {}: {}", slack.game_id, per_color.join(", "));
+                match slack.limiting_color() {
+                    Some((color, missing)) => println!(" (impossible, {missing} {color} short)"),
+                    None => println!(),
+                }
+            }
+        }
+        Command::MinimalBags { fraction } => {
+            for bag in minimal_bags(&games, fraction) {
+                println!("{bag}");
+            }
+        }
+        Command::Filter { query, aggregate } => {
+            let matching = games.iter().filter(|game| query.matches(game));
+            match aggregate {
+                None => matching.for_each(|game| println!("{game}")),
+                Some(Aggregate::Count) => println!("{}", matching.count()),
+                Some(Aggregate::IdSum) => {
+                    println!("{}", matching.map(|game| game.game_id).sum::<u64>())
+                }
+                Some(Aggregate::PowerSum) => println!(
+                    "{}",
+                    matching
+                        .map(|game| game.min_cube_set().power())
+                        .sum::<u64>()
+                ),
+            }
+        }
+        Command::Simulate {
+            games,
+            max_rounds,
+            max_round_size,
+            seed,
+        } => {
+            let mut simulator = DrawSimulator::new(single_bag(&bags)?, rng(seed));
+            for game_id in 1..=games {
+                println!(
+                    "{}",
+                    simulator.random_game(game_id, 1..=max_rounds.max(1), 1..=max_round_size)
+                );
+            }
+        }
+        Command::Likelihood { samples, seed } => {
+            let mut simulator = DrawSimulator::new(single_bag(&bags)?, rng(seed));
+            for game in &games {
+                let probability = simulator.estimate_probability(game, samples);
+                println!("Game {}: {probability}", game.game_id);
+            }
+        }
+        Command::Estimate { max_count } => {
+            let assumed = single_bag(&bags)?;
+            let estimator = BagEstimator::new(&games).max_count(max_count);
+            let estimate = estimator.estimate();
+
+            println!("estimated bag: {}", estimate.bag);
+            for (color, (low, high)) in &estimate.intervals {
+                let capped = if estimate.capped.contains(color) {
+                    ", capped at --max-count, the likelihood may keep growing beyond it"
+                } else {
+                    ""
+                };
+                println!(
+                    "{color}: {} (95% interval {low} to {high}), assumed {}{capped}",
+                    estimate.bag.get(color),
+                    assumed.get(color)
+                );
+            }
+            println!(
+                "log-likelihood: {} for the estimate, {} for the assumed bag",
+                estimate.log_likelihood,
+                estimator.log_likelihood(assumed)
+            );
+        }
+        Command::Stats { format } => {
+            let report = Report::new(&games);
+            match format {
+                Format::Table => print!("{report}"),
+                Format::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            }
+        }
+    }
+
+    Ok(())
 }
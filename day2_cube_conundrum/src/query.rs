@@ -0,0 +1,279 @@
+use nom::{combinator::all_consuming, error, Finish};
+
+use crate::cube_set::{Color, CubeSet};
+use crate::game::Game;
+
+/// Predicate over a game, e.g. `any(red > 10 and blue < 3) or power > 1000`.
+///
+/// Outside of `any(...)` and `all(...)` cube counts and `power` refer to the game's
+/// minimum cube set, inside they refer to each of its rounds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    Or(Box<Query>, Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    /// at least one round matches
+    Any(Box<Query>),
+    /// every round matches
+    All(Box<Query>),
+    Compare(Value, Comparison, Value),
+}
+
+/// Number a query compares
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(u64),
+    /// `id`
+    GameId,
+    /// `rounds`, the number of rounds of the game
+    Rounds,
+    /// `power`
+    Power,
+    /// `total`, the number of cubes of every color
+    Total,
+    /// any other name is the number of cubes of that color
+    Count(Color),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+// names that cannot be used as colors
+const KEYWORDS: [&str; 9] = [
+    "and", "or", "not", "any", "all", "id", "rounds", "power", "total",
+];
+
+impl Query {
+    pub fn try_from_str(input: &str) -> Result<Query, error::Error<&str>> {
+        let (_, query) = all_consuming(parser::query)(input).finish()?;
+        Ok(query)
+    }
+
+    pub fn matches(&self, game: &Game) -> bool {
+        self.eval(game, &game.min_cube_set())
+    }
+
+    fn eval(&self, game: &Game, cubes: &CubeSet) -> bool {
+        match self {
+            Query::Or(lhs, rhs) => lhs.eval(game, cubes) || rhs.eval(game, cubes),
+            Query::And(lhs, rhs) => lhs.eval(game, cubes) && rhs.eval(game, cubes),
+            Query::Not(query) => !query.eval(game, cubes),
+            Query::Any(query) => game.rounds.iter().any(|round| query.eval(game, round)),
+            Query::All(query) => game.rounds.iter().all(|round| query.eval(game, round)),
+            Query::Compare(lhs, comparison, rhs) => {
+                comparison.holds(lhs.eval(game, cubes), rhs.eval(game, cubes))
+            }
+        }
+    }
+}
+
+impl Value {
+    fn eval(&self, game: &Game, cubes: &CubeSet) -> u64 {
+        match self {
+            Value::Number(number) => *number,
+            Value::GameId => game.game_id,
+            Value::Rounds => game.rounds.len() as u64,
+            Value::Power => cubes.power(),
+            Value::Total => cubes.iter().map(|(_, count)| count).sum(),
+            Value::Count(color) => cubes.get(color),
+        }
+    }
+}
+
+impl Comparison {
+    fn holds(&self, lhs: u64, rhs: u64) -> bool {
+        match self {
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Gt => lhs > rhs,
+            Comparison::Ge => lhs >= rhs,
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+        }
+    }
+}
+
+mod parser {
+    use super::*;
+    use nom::{
+        branch::alt,
+        bytes::complete::tag,
+        character::complete::{alpha1, multispace0, u64},
+        combinator::{map, value, verify},
+        multi::fold_many0,
+        sequence::{delimited, preceded, tuple},
+        IResult,
+    };
+
+    // query := and ("or" and)*
+    pub fn query(input: &str) -> IResult<&str, Query> {
+        let (rem, first) = and(input)?;
+        fold_many0(
+            preceded(keyword("or"), and),
+            move || first.clone(),
+            |lhs, rhs| Query::Or(Box::new(lhs), Box::new(rhs)),
+        )(rem)
+    }
+
+    // and := not ("and" not)*
+    fn and(input: &str) -> IResult<&str, Query> {
+        let (rem, first) = not(input)?;
+        fold_many0(
+            preceded(keyword("and"), not),
+            move || first.clone(),
+            |lhs, rhs| Query::And(Box::new(lhs), Box::new(rhs)),
+        )(rem)
+    }
+
+    // not := "not" not | atom
+    fn not(input: &str) -> IResult<&str, Query> {
+        alt((
+            map(preceded(keyword("not"), not), |query| {
+                Query::Not(Box::new(query))
+            }),
+            atom,
+        ))(input)
+    }
+
+    // atom := "(" query ")" | ("any" | "all") "(" query ")" | value comparison value
+    fn atom(input: &str) -> IResult<&str, Query> {
+        alt((
+            parenthesized,
+            map(preceded(keyword("any"), parenthesized), |query| {
+                Query::Any(Box::new(query))
+            }),
+            map(preceded(keyword("all"), parenthesized), |query| {
+                Query::All(Box::new(query))
+            }),
+            map(tuple((value_, comparison, value_)), |(lhs, cmp, rhs)| {
+                Query::Compare(lhs, cmp, rhs)
+            }),
+        ))(input)
+    }
+
+    fn parenthesized(input: &str) -> IResult<&str, Query> {
+        delimited(token("("), query, token(")"))(input)
+    }
+
+    fn value_(input: &str) -> IResult<&str, Value> {
+        alt((
+            map(ws(u64), Value::Number),
+            value(Value::GameId, keyword("id")),
+            value(Value::Rounds, keyword("rounds")),
+            value(Value::Power, keyword("power")),
+            value(Value::Total, keyword("total")),
+            map(
+                ws(verify(alpha1, |name: &str| !KEYWORDS.contains(&name))),
+                |color: &str| Value::Count(color.to_string()),
+            ),
+        ))(input)
+    }
+
+    fn comparison(input: &str) -> IResult<&str, Comparison> {
+        // two-character operators first so that "<=" is not read as "<"
+        alt((
+            value(Comparison::Le, token("<=")),
+            value(Comparison::Ge, token(">=")),
+            value(Comparison::Ne, token("!=")),
+            value(Comparison::Eq, token("==")),
+            value(Comparison::Lt, token("<")),
+            value(Comparison::Gt, token(">")),
+            value(Comparison::Eq, token("=")),
+        ))(input)
+    }
+
+    fn ws<'a, O>(
+        parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+    ) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+        delimited(multispace0, parser, multispace0)
+    }
+
+    fn token<'a>(symbol: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+        ws(tag(symbol))
+    }
+
+    // whole word only, "order" does not start with the keyword "or"
+    fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+        ws(verify(alpha1, move |name: &str| name == word))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::example_games;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("red > 12", &[3, 4])]
+    #[case("any(red > 10 and blue < 15)", &[3])]
+    #[case("any(red > 10) and blue < 15", &[3])]
+    #[case("all(green >= 2)", &[3, 5])]
+    #[case("power > 1000", &[3])]
+    #[case("not power > 1000", &[1, 2, 4, 5])]
+    #[case("id = 1 or id == 2 and rounds != 3", &[1])]
+    #[case("(id = 1 or id = 2) and rounds != 3", &[])]
+    #[case("any(total >= 10)", &[3, 4, 5])]
+    #[case("yellow = 0 and 5 <= red", &[3, 4, 5])]
+    #[case("any(green = 0)", &[1])]
+    #[case("not not (blue<=6)", &[1, 2, 3, 5])]
+    fn test_matches(#[case] input: &str, #[case] expected: &[u64]) {
+        let query = Query::try_from_str(input).unwrap();
+
+        let game_ids: Vec<u64> = example_games()
+            .iter()
+            .filter(|game| query.matches(game))
+            .map(|game| game.game_id)
+            .collect();
+        assert_eq!(game_ids, expected);
+    }
+
+    #[test]
+    fn test_precedence() {
+        let compare = |color: &str| {
+            Query::Compare(
+                Value::Count(color.to_string()),
+                Comparison::Gt,
+                Value::Number(1),
+            )
+        };
+
+        assert_eq!(
+            Query::try_from_str("not red > 1 or blue > 1 and green > 1"),
+            Ok(Query::Or(
+                Box::new(Query::Not(Box::new(compare("red")))),
+                Box::new(Query::And(
+                    Box::new(compare("blue")),
+                    Box::new(compare("green"))
+                ))
+            ))
+        );
+        assert_eq!(
+            Query::try_from_str("orange > 1 or android > 1"),
+            Ok(Query::Or(
+                Box::new(compare("orange")),
+                Box::new(compare("android"))
+            ))
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("red")]
+    #[case("red > ")]
+    #[case("any red > 1")]
+    #[case("(red > 1")]
+    #[case("red > 1 and")]
+    #[case("power > total > 1")]
+    #[case("and > 1")]
+    fn test_invalid_query(#[case] input: &str) {
+        assert!(Query::try_from_str(input).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::cube_set::Color;
+use crate::game::Game;
+
+// largest number of game IDs listed for an extreme in the table
+const LISTED_GAMES: usize = 5;
+
+/// Statistics over all the games, printed as a table or serialized as JSON
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Report {
+    pub games: usize,
+    pub rounds: usize,
+    pub colors: BTreeMap<Color, ColorStats>,
+    /// power of the minimum cube set of each game, `None` without games
+    pub power: Option<Summary>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ColorStats {
+    /// count per round, rounds without the color counting as 0
+    pub counts: Summary,
+    /// number of rounds per count
+    pub histogram: BTreeMap<u64, usize>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub min: Extreme,
+    pub max: Extreme,
+    pub mean: f64,
+    pub median: f64,
+}
+
+/// Extreme value with the games it is found in
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Extreme {
+    pub value: u64,
+    pub game_ids: Vec<u64>,
+}
+
+impl Report {
+    pub fn new(games: &[Game]) -> Self {
+        let mut colors: Vec<&str> = games
+            .iter()
+            .flat_map(|game| &game.rounds)
+            .flat_map(|round| round.iter().map(|(color, _)| color))
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        let colors = colors
+            .into_iter()
+            .filter_map(|color| {
+                let counts: Vec<(u64, u64)> = games
+                    .iter()
+                    .flat_map(|game| {
+                        (game.rounds.iter()).map(|round| (game.game_id, round.get(color)))
+                    })
+                    .collect();
+
+                let mut histogram = BTreeMap::new();
+                for (_, count) in &counts {
+                    *histogram.entry(*count).or_default() += 1;
+                }
+                let counts = Summary::new(&counts)?;
+                Some((color.to_string(), ColorStats { counts, histogram }))
+            })
+            .collect();
+
+        let powers: Vec<(u64, u64)> = games
+            .iter()
+            .map(|game| (game.game_id, game.min_cube_set().power()))
+            .collect();
+
+        Self {
+            games: games.len(),
+            rounds: games.iter().map(|game| game.rounds.len()).sum(),
+            colors,
+            power: Summary::new(&powers),
+        }
+    }
+}
+
+impl Summary {
+    /// Summary of `(game ID, value)` pairs, `None` if there are none
+    fn new(values: &[(u64, u64)]) -> Option<Self> {
+        let mut sorted: Vec<u64> = values.iter().map(|(_, value)| *value).collect();
+        sorted.sort_unstable();
+
+        let (min, max) = (*sorted.first()?, *sorted.last()?);
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] as f64 + sorted[middle] as f64) / 2.0
+        } else {
+            sorted[middle] as f64
+        };
+
+        Some(Self {
+            min: Extreme::new(min, values),
+            max: Extreme::new(max, values),
+            mean: sorted.iter().sum::<u64>() as f64 / sorted.len() as f64,
+            median,
+        })
+    }
+}
+
+impl Extreme {
+    fn new(value: u64, values: &[(u64, u64)]) -> Self {
+        let mut game_ids: Vec<u64> = values
+            .iter()
+            .filter(|(_, other)| *other == value)
+            .map(|(game_id, _)| *game_id)
+            .collect();
+        game_ids.dedup();
+
+        Self { value, game_ids }
+    }
+
+    fn listed_games(&self) -> String {
+        let listed: Vec<String> = (self.game_ids.iter().take(LISTED_GAMES))
+            .map(u64::to_string)
+            .collect();
+        let mut listed = listed.join(", ");
+        if self.game_ids.len() > LISTED_GAMES {
+            listed.push_str(&format!(" (+{})", self.game_ids.len() - LISTED_GAMES));
+        }
+        listed
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} games, {} rounds", self.games, self.rounds)?;
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "{:<8} {:>8} {:>8} {:>10} {:>10}  {:<24}  max in games",
+            "", "min", "max", "mean", "median", "min in games"
+        )?;
+        let rows = (self.colors.iter())
+            .map(|(color, stats)| (color.as_str(), &stats.counts))
+            .chain(self.power.iter().map(|power| ("power", power)));
+        for (name, summary) in rows {
+            writeln!(
+                f,
+                "{:<8} {:>8} {:>8} {:>10.2} {:>10.1}  {:<24}  {}",
+                name,
+                summary.min.value,
+                summary.max.value,
+                summary.mean,
+                summary.median,
+                summary.min.listed_games(),
+                summary.max.listed_games()
+            )?;
+        }
+
+        // only the counts that occur, so a single huge count does not widen the table
+        let mut counts: Vec<u64> = (self.colors.values())
+            .flat_map(|stats| stats.histogram.keys().copied())
+            .collect();
+        counts.sort_unstable();
+        counts.dedup();
+        if !counts.is_empty() {
+            writeln!(f)?;
+            write!(f, "{:<8}", "count")?;
+            for count in &counts {
+                write!(f, " {count:>4}")?;
+            }
+            writeln!(f)?;
+
+            for (color, stats) in &self.colors {
+                write!(f, "{color:<8}")?;
+                for count in &counts {
+                    let rounds = stats.histogram.get(count).copied().unwrap_or(0);
+                    let width = count.to_string().len().max(4);
+                    write!(f, " {rounds:>width$}")?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{example_games, games};
+
+    #[test]
+    fn test_report() {
+        let report = Report::new(&example_games());
+
+        assert_eq!((report.games, report.rounds), (5, 14));
+
+        let red = &report.colors["red"];
+        assert_eq!(
+            red.counts.min,
+            Extreme {
+                value: 0,
+                game_ids: vec![1, 2]
+            }
+        );
+        assert_eq!(
+            red.counts.max,
+            Extreme {
+                value: 20,
+                game_ids: vec![3]
+            }
+        );
+        // 0, 0, 0, 1, 1, 1, 1, 3, 4, 4, 6, 6, 14, 20
+        assert_eq!(red.counts.median, 2.0);
+        assert_eq!(red.histogram.values().sum::<usize>(), 14);
+        assert_eq!(red.histogram[&1], 4);
+
+        let power = report.power.as_ref().unwrap();
+        assert_eq!((power.min.value, power.max.value), (12, 1560));
+        assert_eq!(power.max.game_ids, [3]);
+        assert_eq!(power.mean, 2286.0 / 5.0);
+        assert_eq!(power.median, 48.0);
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = Report::new(&[]);
+
+        assert!(report.colors.is_empty());
+        assert_eq!(report.power, None);
+        // only the header of the table
+        assert!(report.to_string().starts_with("0 games, 0 rounds\n"));
+        assert_eq!(report.to_string().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_histogram_width() {
+        let games = games("Game 1: 1000000 red, 2 blue; 1 red");
+
+        let table = Report::new(&games).to_string();
+
+        let histogram: Vec<&str> = table
+            .lines()
+            .skip_while(|line| !line.starts_with("count"))
+            .collect();
+        assert_eq!(histogram[0], "count       0    1    2 1000000");
+        assert_eq!(histogram[1], "blue        1    0    1       0");
+        assert_eq!(histogram[2], "red         0    1    0       1");
+    }
+
+    #[test]
+    fn test_json() {
+        let report = Report::new(&example_games()[..1]);
+
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["colors"]["blue"]["counts"]["max"]["value"], 6);
+        assert_eq!(json["colors"]["green"]["histogram"]["2"], 2);
+        assert_eq!(json["power"]["min"]["game_ids"][0], 1);
+    }
+}
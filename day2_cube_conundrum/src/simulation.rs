@@ -0,0 +1,184 @@
+use std::ops::RangeInclusive;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::cube_set::{Color, CubeSet};
+use crate::game::Game;
+
+/// Plays the game of the puzzle: cubes are drawn at random from the bag without replacement
+/// and all put back in the bag before the next round.
+pub struct DrawSimulator<R> {
+    bag: CubeSet,
+    colors: Vec<Color>,
+    // position in `colors` of the color of every cube in the bag
+    cubes: Vec<usize>,
+    rng: R,
+}
+
+impl<R: Rng> DrawSimulator<R> {
+    pub fn new(bag: &CubeSet, rng: R) -> Self {
+        let colors = bag.iter().map(|(color, _)| color.to_string()).collect();
+        let cubes = bag
+            .iter()
+            .enumerate()
+            .flat_map(|(color_id, (_, count))| (0..count).map(move |_| color_id))
+            .collect();
+
+        Self {
+            bag: bag.clone(),
+            colors,
+            cubes,
+            rng,
+        }
+    }
+
+    /// Number of cubes in the bag
+    pub fn bag_size(&self) -> usize {
+        self.cubes.len()
+    }
+
+    /// Draw `size` cubes from the full bag.
+    ///
+    /// # Panics
+    ///
+    /// If the bag holds fewer than `size` cubes.
+    pub fn draw_round(&mut self, size: usize) -> CubeSet {
+        assert!(
+            size <= self.bag_size(),
+            "cannot draw {size} cubes from a bag of {}",
+            self.bag_size()
+        );
+
+        let mut counts = vec![0; self.colors.len()];
+        for color_id in self.cubes.choose_multiple(&mut self.rng, size) {
+            counts[*color_id] += 1;
+        }
+
+        let drawn = (counts.into_iter().zip(&self.colors))
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, color)| (count, color.clone()))
+            .collect::<Vec<_>>();
+        CubeSet::from(drawn)
+    }
+
+    /// Game with one round of each of the given sizes
+    pub fn game(&mut self, game_id: u64, round_sizes: &[usize]) -> Game {
+        let rounds = round_sizes
+            .iter()
+            .map(|size| self.draw_round(*size))
+            .collect();
+        Game::from((game_id, rounds))
+    }
+
+    /// Game with a random number of rounds of random sizes, round sizes are capped to the bag size
+    pub fn random_game(
+        &mut self,
+        game_id: u64,
+        rounds: RangeInclusive<usize>,
+        round_sizes: RangeInclusive<usize>,
+    ) -> Game {
+        let max_size = (*round_sizes.end()).min(self.bag_size());
+        let min_size = (*round_sizes.start()).min(max_size);
+
+        let sizes: Vec<usize> = (0..self.rng.gen_range(rounds))
+            .map(|_| self.rng.gen_range(min_size..=max_size))
+            .collect();
+        self.game(game_id, &sizes)
+    }
+
+    /// Monte Carlo estimate of the probability that playing with this bag gives exactly the rounds
+    /// of `game`. Rounds are independent since the cubes are put back in between, so each round is
+    /// estimated on its own from `samples` draws of its size, and the estimates are multiplied.
+    ///
+    /// # Panics
+    ///
+    /// If `samples` is 0.
+    pub fn estimate_probability(&mut self, game: &Game, samples: usize) -> f64 {
+        assert!(samples > 0, "cannot estimate a probability from 0 samples");
+        game.rounds
+            .iter()
+            .map(|round| {
+                let size = round.iter().map(|(_, count)| count as usize).sum::<usize>();
+                if !round.is_subset_of(&self.bag) {
+                    return 0.0;
+                }
+
+                let hits = (0..samples)
+                    .filter(|_| self.draw_round(size) == *round)
+                    .count();
+                hits as f64 / samples as f64
+            })
+            .product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_set::BAG;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rstest::rstest;
+
+    fn simulator(bag: &CubeSet) -> DrawSimulator<StdRng> {
+        DrawSimulator::new(bag, StdRng::seed_from_u64(2023))
+    }
+
+    #[test]
+    fn test_games_are_possible() {
+        let mut simulator = simulator(&BAG);
+        assert_eq!(simulator.bag_size(), 39);
+
+        for game_id in 1..=100 {
+            let game = simulator.random_game(game_id, 1..=6, 1..=50);
+
+            assert_eq!(game.game_id, game_id);
+            assert!((1..=6).contains(&game.rounds.len()));
+            assert!(game.is_possible(&BAG), "{game}");
+        }
+    }
+
+    #[test]
+    fn test_round_sizes() {
+        let game = simulator(&BAG).game(1, &[0, 1, 39]);
+
+        let sizes: Vec<u64> = game
+            .rounds
+            .iter()
+            .map(|round| round.iter().map(|(_, count)| count).sum())
+            .collect();
+        assert_eq!(sizes, [0, 1, 39]);
+        assert_eq!(game.rounds[2], *BAG);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot draw 2 cubes from a bag of 1")]
+    fn test_draw_more_than_bag() {
+        simulator(&CubeSet::from([(1, "red")])).draw_round(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot estimate a probability from 0 samples")]
+    fn test_no_samples() {
+        let game = simulator(&BAG).game(1, &[1]);
+        simulator(&BAG).estimate_probability(&game, 0);
+    }
+
+    #[rstest]
+    // 1 in 2 chance to draw the red cube
+    #[case("1 red, 1 blue", "Game 1: 1 red", 0.5)]
+    // 4 pairs out of 6 hold a red cube and a blue one, twice
+    #[case("2 red, 2 blue", "Game 1: 1 red, 1 blue; 1 blue, 1 red", 4.0 / 9.0)]
+    #[case("2 red, 2 blue", "Game 1: 2 red, 2 blue", 1.0)]
+    #[case("2 red, 2 blue", "Game 1: 1 red; 3 blue", 0.0)]
+    #[case("2 red, 2 blue", "Game 1: 1 green", 0.0)]
+    fn test_estimate_probability(#[case] bag: &str, #[case] game: &str, #[case] expected: f64) {
+        let bag = CubeSet::try_from_str(bag).unwrap();
+        let game = Game::try_from_str(game).unwrap();
+
+        let estimate = simulator(&bag).estimate_probability(&game, 10_000);
+
+        assert!((estimate - expected).abs() < 0.02, "{estimate}");
+    }
+}
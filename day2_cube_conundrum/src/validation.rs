@@ -0,0 +1,213 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use nom::combinator::all_consuming;
+use nom::Finish;
+
+use crate::cube_set::{Color, CubeSet};
+use crate::game::{parser::raw_game, Game};
+
+/// How strictly the puzzle input is checked
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// duplicate colors in a round are summed and zero counts are dropped
+    #[default]
+    Lenient,
+    /// duplicate colors, zero counts, games without cubes and game IDs that
+    /// do not count up from 1 are rejected
+    Strict,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    Syntax(String),
+    DuplicateColor { round: usize, color: Color },
+    ZeroCount { round: usize, color: Color },
+    EmptyGame,
+    DuplicateGameId { game_id: u64, first_line: usize },
+    NonSequentialGameId { game_id: u64, expected: u64 },
+}
+
+/// Violation found on a line of the puzzle input, lines are numbered from 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineViolation {
+    pub line: usize,
+    pub violation: Violation,
+}
+
+impl fmt::Display for LineViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.violation {
+            Violation::Syntax(err) => write!(f, "invalid game: {err}"),
+            Violation::DuplicateColor { round, color } => {
+                write!(f, "{color} appears more than once in round {round}")
+            }
+            Violation::ZeroCount { round, color } => {
+                write!(f, "zero {color} cubes in round {round}")
+            }
+            Violation::EmptyGame => write!(f, "game without any cube"),
+            Violation::DuplicateGameId {
+                game_id,
+                first_line,
+            } => write!(f, "game {game_id} already appears on line {first_line}"),
+            Violation::NonSequentialGameId { game_id, expected } => {
+                write!(f, "game {game_id} found where game {expected} was expected")
+            }
+        }
+    }
+}
+
+/// Parse one game per line, reporting every violation of the given mode
+/// rather than stopping at the first one.
+pub fn parse_games(input: &str, mode: ParseMode) -> Result<Vec<Game>, Vec<LineViolation>> {
+    let mut games = Vec::new();
+    let mut violations = Vec::new();
+    // line of every game ID seen so far
+    let mut first_lines: HashMap<u64, usize> = HashMap::new();
+    let mut expected_game_id = 1;
+
+    for (idx, line) in input.lines().enumerate() {
+        let line_no = idx + 1;
+        let mut report = |violation| {
+            violations.push(LineViolation {
+                line: line_no,
+                violation,
+            })
+        };
+
+        let (game_id, rounds) = match all_consuming(raw_game)(line).finish() {
+            Ok((_, game)) => game,
+            Err(err) => {
+                report(Violation::Syntax(err.to_string()));
+                continue;
+            }
+        };
+
+        if mode == ParseMode::Strict {
+            for violation in round_violations(&rounds) {
+                report(violation);
+            }
+
+            if let Some(first_line) = first_lines.get(&game_id) {
+                report(Violation::DuplicateGameId {
+                    game_id,
+                    first_line: *first_line,
+                });
+            } else if game_id != expected_game_id {
+                report(Violation::NonSequentialGameId {
+                    game_id,
+                    expected: expected_game_id,
+                });
+            }
+            first_lines.entry(game_id).or_insert(line_no);
+            expected_game_id = game_id + 1;
+        }
+
+        let rounds = rounds.into_iter().map(CubeSet::from).collect();
+        games.push(Game::from((game_id, rounds)));
+    }
+
+    if violations.is_empty() {
+        Ok(games)
+    } else {
+        Err(violations)
+    }
+}
+
+fn round_violations(rounds: &[Vec<(u64, Color)>]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (idx, round) in rounds.iter().enumerate() {
+        let round_no = idx + 1;
+        let mut seen = BTreeSet::new();
+        for (count, color) in round {
+            if !seen.insert(color) {
+                violations.push(Violation::DuplicateColor {
+                    round: round_no,
+                    color: color.clone(),
+                });
+            }
+            if *count == 0 {
+                violations.push(Violation::ZeroCount {
+                    round: round_no,
+                    color: color.clone(),
+                });
+            }
+        }
+    }
+
+    if rounds.iter().flatten().all(|(count, _)| *count == 0) {
+        violations.push(Violation::EmptyGame);
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(ParseMode::Lenient)]
+    #[case(ParseMode::Strict)]
+    fn test_valid_input(#[case] mode: ParseMode) {
+        let games = parse_games(include_str!("example.txt"), mode).unwrap();
+        assert_eq!(games.len(), 5);
+
+        let games = parse_games(include_str!("input.txt"), mode).unwrap();
+        assert_eq!(games.len(), 100);
+    }
+
+    #[test]
+    fn test_lenient_keeps_sums_and_drops_zero_counts() {
+        let input = "Game 1: 3 red, 4 red; 0 blue\nGame 1: 0 green\nGame 7: 1 red";
+
+        let games = parse_games(input, ParseMode::Lenient).unwrap();
+
+        assert_eq!(
+            games[0].rounds,
+            [CubeSet::from([(7, "red")]), CubeSet::default()]
+        );
+        assert_eq!(games[1].rounds, [CubeSet::default()]);
+        assert_eq!(games[2].game_id, 7);
+    }
+
+    #[test]
+    fn test_strict_reports_every_violation() {
+        let input = "\
+Game 1: 3 red, 4 red; 0 blue
+Game 1: 0 green
+Game 7: 1 red
+Game 8 1 red
+Game 8: 2 blue, 1 red, 2 blue";
+
+        let violations: Vec<String> = parse_games(input, ParseMode::Strict)
+            .unwrap_err()
+            .iter()
+            .map(LineViolation::to_string)
+            .collect();
+
+        assert_eq!(
+            violations,
+            [
+                "line 1: red appears more than once in round 1",
+                "line 1: zero blue cubes in round 2",
+                "line 2: zero green cubes in round 1",
+                "line 2: game without any cube",
+                "line 2: game 1 already appears on line 1",
+                "line 3: game 7 found where game 2 was expected",
+                "line 4: invalid game: error Tag at:  1 red",
+                "line 5: blue appears more than once in round 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors_in_lenient_mode() {
+        let violations = parse_games("Game 1: 1 red\nGame 2: red", ParseMode::Lenient).unwrap_err();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, 2);
+    }
+}
@@ -0,0 +1,106 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use day3_gear_ratios::adjacency::AdjacencyGraph;
+use day3_gear_ratios::grid_index::GridIndex;
+use day3_gear_ratios::schematic::Schematic;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const INPUT: &str = include_str!("../src/input.txt");
+const SYMBOLS: &[u8] = b"*#$%&+-/=@";
+
+// square schematic with the same mix of periods, numbers and symbols as the puzzle input
+fn generate(size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut schematic = String::with_capacity(size * (size + 1));
+
+    for _ in 0..size {
+        let mut col = 0;
+        while col < size {
+            match rng.gen_range(0..10) {
+                0..=6 => schematic.push('.'),
+                7 | 8 => {
+                    // a period after the number keeps it from running into the next one
+                    let digits = rng.gen_range(1..=3).min(size - col);
+                    for _ in 0..digits {
+                        schematic.push(char::from(b'0' + rng.gen_range(0..10)));
+                    }
+                    col += digits;
+                    if col < size {
+                        schematic.push('.');
+                    }
+                }
+                _ => schematic.push(char::from(SYMBOLS[rng.gen_range(0..SYMBOLS.len())])),
+            }
+            col += 1;
+        }
+        schematic.push('\n');
+    }
+    schematic
+}
+
+fn inputs() -> Vec<(String, String)> {
+    let mut inputs = vec![("input".to_string(), INPUT.to_string())];
+    for size in [250, 500, 1000] {
+        inputs.push((format!("generated_{size}"), generate(size)));
+    }
+    inputs
+}
+
+fn bench_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+    for (name, input) in inputs() {
+        group.bench_with_input(BenchmarkId::new("schematic", &name), &input, |b, input| {
+            b.iter(|| Schematic::new(black_box(input)))
+        });
+
+        let schematic = Schematic::new(&input);
+        group.bench_with_input(
+            BenchmarkId::new("grid_index", &name),
+            &schematic,
+            |b, schematic| b.iter(|| GridIndex::new(black_box(schematic))),
+        );
+        // the schematic builds its graph once, on the first query
+        group.bench_with_input(
+            BenchmarkId::new("adjacency_graph", &name),
+            &schematic,
+            |b, schematic| b.iter(|| AdjacencyGraph::new(black_box(schematic))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let mut part1 = c.benchmark_group("part1");
+    let inputs: Vec<_> = inputs()
+        .into_iter()
+        .map(|(name, input)| {
+            let schematic = Schematic::new(&input);
+            let index = GridIndex::new(&schematic);
+            (name, schematic, index)
+        })
+        .collect();
+
+    for (name, schematic, index) in &inputs {
+        part1.bench_function(BenchmarkId::new("schematic", name), |b| {
+            b.iter(|| black_box(schematic).sum_eligible_numbers())
+        });
+        part1.bench_function(BenchmarkId::new("grid_index", name), |b| {
+            b.iter(|| black_box(index).sum_eligible_numbers())
+        });
+    }
+    part1.finish();
+
+    let mut part2 = c.benchmark_group("part2");
+    for (name, schematic, index) in &inputs {
+        part2.bench_function(BenchmarkId::new("schematic", name), |b| {
+            b.iter(|| black_box(schematic).gear_ratio())
+        });
+        part2.bench_function(BenchmarkId::new("grid_index", name), |b| {
+            b.iter(|| black_box(index).gear_ratio())
+        });
+    }
+    part2.finish();
+}
+
+criterion_group!(benches, bench_construction, bench_queries);
+criterion_main!(benches);
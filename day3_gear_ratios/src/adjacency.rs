@@ -0,0 +1,186 @@
+use crate::grid_index::GridIndex;
+use crate::schematic::{Schematic, SchematicIndex, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberNode {
+    pub idx: SchematicIndex,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolNode {
+    pub idx: SchematicIndex,
+    pub kind: char,
+}
+
+/// Bipartite graph linking every number of a schematic to the symbols it touches under the
+/// schematic's neighbourhood.
+///
+/// Numbers and symbols are ordered by position, and so are the neighbours of every node.
+#[derive(Debug)]
+pub struct AdjacencyGraph {
+    numbers: Vec<NumberNode>,
+    symbols: Vec<SymbolNode>,
+    // positions in `symbols` of the symbols touching every number
+    number_edges: Vec<Vec<usize>>,
+    // positions in `numbers` of the numbers touching every symbol
+    symbol_edges: Vec<Vec<usize>>,
+}
+
+impl AdjacencyGraph {
+    pub fn new(schematic: &Schematic) -> Self {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        // position in `numbers` of every token that is a number
+        let mut number_ids = Vec::new();
+
+        for (idx, value) in schematic.tokens() {
+            match value {
+                Value::Num(value) => {
+                    number_ids.push(Some(numbers.len()));
+                    numbers.push(NumberNode { idx, value: *value });
+                }
+                Value::Symbol(kind) => {
+                    number_ids.push(None);
+                    symbols.push(SymbolNode { idx, kind: *kind });
+                }
+            }
+        }
+
+        // symbols are a single cell wide, so looking around them is enough to find every edge
+        let index = GridIndex::new(schematic);
+        let mut number_edges = vec![Vec::new(); numbers.len()];
+        let mut symbol_edges = Vec::with_capacity(symbols.len());
+        for (symbol_id, symbol) in symbols.iter().enumerate() {
+            let adjacent_numbers: Vec<usize> = (index.adjacent_token_ids(symbol.idx).into_iter())
+                .filter_map(|token_id| number_ids[token_id])
+                .collect();
+
+            for number_id in &adjacent_numbers {
+                number_edges[*number_id].push(symbol_id);
+            }
+            symbol_edges.push(adjacent_numbers);
+        }
+
+        Self {
+            numbers,
+            symbols,
+            number_edges,
+            symbol_edges,
+        }
+    }
+
+    /// Every number with the symbols it touches
+    pub fn numbers(
+        &self,
+    ) -> impl Iterator<Item = (&NumberNode, impl Iterator<Item = &SymbolNode>)> {
+        (self.numbers.iter().zip(&self.number_edges)).map(|(number, edges)| {
+            let symbols = edges.iter().map(|symbol_id| &self.symbols[*symbol_id]);
+            (number, symbols)
+        })
+    }
+
+    /// Every symbol with the numbers it touches
+    pub fn symbols(
+        &self,
+    ) -> impl Iterator<Item = (&SymbolNode, impl Iterator<Item = &NumberNode>)> {
+        (self.symbols.iter().zip(&self.symbol_edges)).map(|(symbol, edges)| {
+            let numbers = edges.iter().map(|number_id| &self.numbers[*number_id]);
+            (symbol, numbers)
+        })
+    }
+
+    pub(crate) fn number(&self, number_id: usize) -> &NumberNode {
+        &self.numbers[number_id]
+    }
+
+    pub(crate) fn symbol(&self, symbol_id: usize) -> &SymbolNode {
+        &self.symbols[symbol_id]
+    }
+
+    pub(crate) fn number_count(&self) -> usize {
+        self.numbers.len()
+    }
+
+    pub(crate) fn symbol_count(&self) -> usize {
+        self.symbols.len()
+    }
+
+    // positions of the symbols touching the number at `number_id`
+    pub(crate) fn symbols_of(&self, number_id: usize) -> &[usize] {
+        &self.number_edges[number_id]
+    }
+
+    // positions of the numbers touching the symbol at `symbol_id`
+    pub(crate) fn numbers_of(&self, symbol_id: usize) -> &[usize] {
+        &self.symbol_edges[symbol_id]
+    }
+
+    /// Numbers touching at least one symbol
+    pub fn part_numbers(&self) -> impl Iterator<Item = &NumberNode> {
+        (self.numbers.iter().zip(&self.number_edges))
+            .filter(|(_, edges)| !edges.is_empty())
+            .map(|(number, _)| number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(467, &[(1, '*')])]
+    #[case(114, &[])]
+    #[case(617, &[(4, '*')])]
+    #[case(592, &[(5, '+')])]
+    #[case(598, &[(8, '*')])]
+    fn test_symbols_of_number(#[case] value: u64, #[case] expected: &[(usize, char)]) {
+        let graph = AdjacencyGraph::new(&Schematic::new(include_str!("example.txt")));
+
+        let (_, symbols) = graph
+            .numbers()
+            .find(|(number, _)| number.value == value)
+            .unwrap();
+        let symbols: Vec<(usize, char)> =
+            symbols.map(|symbol| (symbol.idx.0, symbol.kind)).collect();
+        assert_eq!(symbols, expected);
+    }
+
+    #[rstest]
+    #[case((1, (3, 4)), &[467, 35])]
+    #[case((3, (6, 7)), &[633])]
+    #[case((4, (3, 4)), &[617])]
+    #[case((8, (3, 4)), &[664])]
+    fn test_numbers_of_symbol(#[case] idx: SchematicIndex, #[case] expected: &[u64]) {
+        let graph = AdjacencyGraph::new(&Schematic::new(include_str!("example.txt")));
+
+        let (_, numbers) = graph
+            .symbols()
+            .find(|(symbol, _)| symbol.idx == idx)
+            .unwrap();
+        let numbers: Vec<u64> = numbers.map(|number| number.value).collect();
+        assert_eq!(numbers, expected);
+    }
+
+    #[test]
+    fn test_shared_tokens() {
+        // `*` touches both numbers, and 12 touches both symbols
+        let graph = AdjacencyGraph::new(&Schematic::new("12*34\n#...."));
+
+        let edges: Vec<(u64, String)> = graph
+            .numbers()
+            .map(|(number, symbols)| (number.value, symbols.map(|symbol| symbol.kind).collect()))
+            .collect();
+        assert_eq!(edges, [(12, "*#".to_string()), (34, "*".to_string())]);
+
+        let edges: Vec<(SchematicIndex, Vec<u64>)> = graph
+            .symbols()
+            .map(|(symbol, numbers)| (symbol.idx, numbers.map(|number| number.value).collect()))
+            .collect();
+        assert_eq!(
+            edges,
+            [((0, (2, 3)), vec![12, 34]), ((1, (0, 1)), vec![12])]
+        );
+    }
+}
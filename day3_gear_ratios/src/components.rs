@@ -0,0 +1,160 @@
+use std::ops::Range;
+
+use crate::adjacency::{AdjacencyGraph, NumberNode, SymbolNode};
+use crate::gear_rule::Aggregation;
+
+/// Numbers and symbols linked by chains of adjacency, number to symbol to number and so on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// numbers of the component, ordered by position
+    pub numbers: Vec<NumberNode>,
+    /// symbols of the component, ordered by position
+    pub symbols: Vec<SymbolNode>,
+}
+
+/// Rows and columns covered by a component, end excluded like the spans of tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub rows: Range<usize>,
+    pub cols: Range<usize>,
+}
+
+impl Component {
+    pub fn bounding_box(&self) -> BoundingBox {
+        let spans: Vec<_> = (self.numbers.iter().map(|number| number.idx))
+            .chain(self.symbols.iter().map(|symbol| symbol.idx))
+            .collect();
+
+        // components always hold a number, so there is at least one span
+        let rows = spans.iter().map(|(row, _)| *row);
+        let starts = spans.iter().map(|(_, (start, _))| *start);
+        let ends = spans.iter().map(|(_, (_, end))| *end);
+        BoundingBox {
+            rows: rows.clone().min().unwrap()..rows.max().unwrap() + 1,
+            cols: starts.min().unwrap()..ends.max().unwrap(),
+        }
+    }
+
+    /// Numbers of the component combined with `aggregation`
+    pub fn value(&self, aggregation: &Aggregation) -> u64 {
+        let parts: Vec<u64> = self.numbers.iter().map(|number| number.value).collect();
+        aggregation.apply(&parts)
+    }
+}
+
+/// Connected components of the graph, ordered by their first number.
+///
+/// Numbers without symbols around them and symbols without numbers are left out.
+pub fn components(graph: &AdjacencyGraph) -> Vec<Component> {
+    let mut seen_numbers = vec![false; graph.number_count()];
+    let mut seen_symbols = vec![false; graph.symbol_count()];
+    let mut components = Vec::new();
+
+    for first in 0..graph.number_count() {
+        if seen_numbers[first] || graph.symbols_of(first).is_empty() {
+            continue;
+        }
+
+        let (mut number_ids, mut symbol_ids) = (vec![first], Vec::new());
+        seen_numbers[first] = true;
+        // numbers of the component whose symbols have not been looked at yet
+        let mut pending = vec![first];
+        while let Some(number_id) = pending.pop() {
+            for symbol_id in graph.symbols_of(number_id) {
+                if seen_symbols[*symbol_id] {
+                    continue;
+                }
+                seen_symbols[*symbol_id] = true;
+                symbol_ids.push(*symbol_id);
+
+                for next in graph.numbers_of(*symbol_id) {
+                    if !seen_numbers[*next] {
+                        seen_numbers[*next] = true;
+                        number_ids.push(*next);
+                        pending.push(*next);
+                    }
+                }
+            }
+        }
+
+        number_ids.sort_unstable();
+        symbol_ids.sort_unstable();
+        components.push(Component {
+            numbers: (number_ids.into_iter())
+                .map(|number_id| graph.number(number_id).clone())
+                .collect(),
+            symbols: (symbol_ids.into_iter())
+                .map(|symbol_id| graph.symbol(symbol_id).clone())
+                .collect(),
+        });
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematic::Schematic;
+
+    fn summary(component: &Component) -> (Vec<u64>, String) {
+        (
+            component
+                .numbers
+                .iter()
+                .map(|number| number.value)
+                .collect(),
+            component.symbols.iter().map(|symbol| symbol.kind).collect(),
+        )
+    }
+
+    #[test]
+    fn test_example_components() {
+        let schematic = Schematic::new(include_str!("example.txt"));
+
+        let components = schematic.components();
+
+        let summaries: Vec<(Vec<u64>, String)> = components.iter().map(summary).collect();
+        assert_eq!(
+            summaries,
+            [
+                (vec![467, 35], "*".to_string()),
+                (vec![633], "#".to_string()),
+                (vec![617], "*".to_string()),
+                (vec![592], "+".to_string()),
+                (vec![755, 598], "*".to_string()),
+                (vec![664], "$".to_string()),
+            ]
+        );
+        assert_eq!(
+            components[0].bounding_box(),
+            BoundingBox {
+                rows: 0..3,
+                cols: 0..4
+            }
+        );
+        assert_eq!(components[4].value(&Aggregation::Sum), 755 + 598);
+    }
+
+    #[test]
+    fn test_chained_components() {
+        // the two gears share 2, and 4 joins through the `#` below 3
+        let schematic = Schematic::new("1*2*3.\n....#4\n7.....");
+
+        let components = schematic.components();
+
+        assert_eq!(components.len(), 1);
+        assert_eq!(
+            summary(&components[0]),
+            (vec![1, 2, 3, 4], "**#".to_string())
+        );
+        assert_eq!(
+            components[0].bounding_box(),
+            BoundingBox {
+                rows: 0..2,
+                cols: 0..6
+            }
+        );
+        assert_eq!(components[0].value(&Aggregation::Product), 24);
+    }
+}
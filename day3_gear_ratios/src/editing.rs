@@ -0,0 +1,233 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::gear_rule::GearRule;
+use crate::schematic::{Schematic, Value};
+
+/// Schematic that can be edited cell by cell and row by row, keeping the answers of both parts
+/// up to date.
+///
+/// An edit only changes which numbers touch a symbol, and which numbers a gear touches, in the
+/// edited rows and the rows right next to them, so only those are looked at again.
+///
+/// This only holds for the 8 cells around every cell, so the schematic always uses the
+/// neighbourhood of the puzzle and cannot be given another one with
+/// [`Schematic::with_neighbourhood`] or [`Schematic::with_wrapping_edges`].
+#[derive(Debug)]
+pub struct EditableSchematic {
+    lines: Vec<Vec<char>>,
+    schematic: Schematic,
+    part_sum: u64,
+    gear_ratio: u64,
+}
+
+impl EditableSchematic {
+    pub fn new(input: &str) -> Self {
+        let lines: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+        let schematic = Schematic::new(input);
+        let (part_sum, gear_ratio) = row_totals(&schematic, 0..=lines.len());
+
+        Self {
+            lines,
+            schematic,
+            part_sum,
+            gear_ratio,
+        }
+    }
+
+    pub fn schematic(&self) -> &Schematic {
+        &self.schematic
+    }
+
+    pub fn sum_eligible_numbers(&self) -> u64 {
+        self.part_sum
+    }
+
+    pub fn gear_ratio(&self) -> u64 {
+        self.gear_ratio
+    }
+
+    /// Writes `c` at `col` of `row`, padding the row with periods if it is shorter.
+    ///
+    /// Panics if `row` is out of bounds.
+    pub fn set_cell(&mut self, row: usize, col: usize, c: char) {
+        self.edit(around(row), around(row), |lines, schematic| {
+            let line = &mut lines[row];
+            if line.len() <= col {
+                line.resize(col + 1, '.');
+            }
+            line[col] = c;
+            schematic.replace_row(row, &line.iter().collect::<String>());
+        });
+    }
+
+    /// Inserts `line` before `row`, or after the last row if `row` is the number of rows.
+    ///
+    /// Panics if `row` is greater than the number of rows.
+    pub fn insert_row(&mut self, row: usize, line: &str) {
+        // the rows around the new one no longer touch each other
+        let before = row.saturating_sub(1)..=row;
+        self.edit(before, around(row), |lines, schematic| {
+            lines.insert(row, line.chars().collect());
+            schematic.shift_rows_down(row);
+            schematic.replace_row(row, line);
+        });
+    }
+
+    /// Removes `row` and returns it.
+    ///
+    /// Panics if `row` is out of bounds.
+    pub fn delete_row(&mut self, row: usize) -> String {
+        let mut deleted = Vec::new();
+        // the rows around the deleted one now touch each other
+        let after = row.saturating_sub(1)..=row;
+        self.edit(around(row), after, |lines, schematic| {
+            deleted = lines.remove(row);
+            schematic.remove_row(row);
+        });
+        deleted.into_iter().collect()
+    }
+
+    // takes the totals of the `before` rows out, edits, and adds the totals of the `after` rows
+    fn edit(
+        &mut self,
+        before: RangeInclusive<usize>,
+        after: RangeInclusive<usize>,
+        edit: impl FnOnce(&mut Vec<Vec<char>>, &mut Schematic),
+    ) {
+        let (part_sum, gear_ratio) = row_totals(&self.schematic, before);
+        self.part_sum -= part_sum;
+        self.gear_ratio -= gear_ratio;
+
+        edit(&mut self.lines, &mut self.schematic);
+
+        let (part_sum, gear_ratio) = row_totals(&self.schematic, after);
+        self.part_sum += part_sum;
+        self.gear_ratio += gear_ratio;
+    }
+}
+
+impl fmt::Display for EditableSchematic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+// rows whose tokens may touch the tokens of `row` under the neighbourhood of the puzzle
+fn around(row: usize) -> RangeInclusive<usize> {
+    row.saturating_sub(1)..=row + 1
+}
+
+// sum of the part numbers and of the gear ratios of the tokens in `rows`
+fn row_totals(schematic: &Schematic, rows: RangeInclusive<usize>) -> (u64, u64) {
+    let gear_rule = GearRule::default();
+    let (mut part_sum, mut gear_ratio) = (0, 0);
+
+    for (idx, value) in rows.flat_map(|row| schematic.row_tokens(row)) {
+        let around = schematic.neighbours(idx);
+        match value {
+            Value::Num(n) if around.iter().any(|(_, v)| v.is_symbol()) => part_sum += n,
+            Value::Symbol(_) if value.is_gear() => {
+                let parts: Vec<u64> = around.iter().filter_map(|(_, v)| v.get_num()).collect();
+                gear_ratio += gear_rule.apply(&parts).unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+
+    (part_sum, gear_ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn assert_same_as_rebuild(schematic: &EditableSchematic) {
+        let rebuilt = Schematic::new(&schematic.to_string());
+        assert_eq!(
+            (schematic.sum_eligible_numbers(), schematic.gear_ratio()),
+            (rebuilt.sum_eligible_numbers(), rebuilt.gear_ratio()),
+            "\n{schematic}"
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        let schematic = EditableSchematic::new(include_str!("example.txt"));
+
+        assert_eq!(schematic.sum_eligible_numbers(), 4361);
+        assert_eq!(schematic.gear_ratio(), 467835);
+        assert_eq!(
+            schematic.to_string(),
+            format!("{}\n", include_str!("example.txt"))
+        );
+    }
+
+    #[test]
+    fn test_edits() {
+        let mut schematic = EditableSchematic::new("467..\n...*.\n..35.");
+
+        // 467 and 35 lose their gear
+        schematic.set_cell(1, 3, '.');
+        assert_eq!(
+            (schematic.sum_eligible_numbers(), schematic.gear_ratio()),
+            (0, 0)
+        );
+
+        // a row between the gear and 35 leaves the gear with a single part
+        schematic.set_cell(1, 3, '*');
+        schematic.insert_row(2, ".....");
+        assert_eq!(
+            (schematic.sum_eligible_numbers(), schematic.gear_ratio()),
+            (467, 0)
+        );
+
+        assert_eq!(schematic.delete_row(2), ".....");
+        assert_eq!(
+            (schematic.sum_eligible_numbers(), schematic.gear_ratio()),
+            (467 + 35, 467 * 35)
+        );
+
+        // the new digit joins 35 into 352, past the end of the row
+        schematic.set_cell(2, 5, '2');
+        assert_eq!(schematic.to_string(), "467..\n...*.\n..35.2\n");
+        schematic.set_cell(2, 4, '0');
+        assert_eq!(schematic.gear_ratio(), 467 * 3502);
+    }
+
+    #[test]
+    fn test_random_edits() {
+        const CELLS: &[char] = &['.', '.', '.', '.', '1', '2', '5', '9', '*', '*', '#', '$'];
+        let mut rng = StdRng::seed_from_u64(2023);
+        let random_line = |rng: &mut StdRng| -> String {
+            (0..rng.gen_range(0..12))
+                .map(|_| CELLS[rng.gen_range(0..CELLS.len())])
+                .collect()
+        };
+
+        let mut schematic = EditableSchematic::new(include_str!("example.txt"));
+        for _ in 0..1000 {
+            let rows = schematic.lines.len();
+            match rng.gen_range(0..10) {
+                0 => {
+                    let line = random_line(&mut rng);
+                    schematic.insert_row(rng.gen_range(0..=rows), &line);
+                }
+                1 if rows > 0 => {
+                    schematic.delete_row(rng.gen_range(0..rows));
+                }
+                _ if rows > 0 => {
+                    let c = CELLS[rng.gen_range(0..CELLS.len())];
+                    schematic.set_cell(rng.gen_range(0..rows), rng.gen_range(0..12), c);
+                }
+                _ => schematic.insert_row(0, "1*1"),
+            }
+            assert_same_as_rebuild(&schematic);
+        }
+    }
+}
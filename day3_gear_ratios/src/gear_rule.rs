@@ -0,0 +1,129 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Number of parts around a gear for it to count
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartCount {
+    Exactly(usize),
+    AtLeast(usize),
+    Between(RangeInclusive<usize>),
+}
+
+impl PartCount {
+    pub fn matches(&self, count: usize) -> bool {
+        match self {
+            Self::Exactly(parts) => count == *parts,
+            Self::AtLeast(parts) => count >= *parts,
+            Self::Between(parts) => parts.contains(&count),
+        }
+    }
+}
+
+/// Aggregation written by the caller
+pub type AggregateFn = dyn Fn(&[u64]) -> u64 + Send + Sync;
+
+/// How the parts around a gear are combined into its value
+#[derive(Clone)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    /// Largest part, 0 without parts
+    Max,
+    Custom(Arc<AggregateFn>),
+}
+
+impl Aggregation {
+    pub fn custom(f: impl Fn(&[u64]) -> u64 + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+
+    pub fn apply(&self, parts: &[u64]) -> u64 {
+        match self {
+            Self::Product => parts.iter().product(),
+            Self::Sum => parts.iter().sum(),
+            Self::Max => parts.iter().copied().max().unwrap_or(0),
+            Self::Custom(f) => f(parts),
+        }
+    }
+}
+
+impl fmt::Debug for Aggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Product => write!(f, "Product"),
+            Self::Sum => write!(f, "Sum"),
+            Self::Max => write!(f, "Max"),
+            Self::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Which gears count and what they are worth
+#[derive(Debug, Clone)]
+pub struct GearRule {
+    pub parts: PartCount,
+    pub aggregation: Aggregation,
+}
+
+impl Default for GearRule {
+    /// Rule of the puzzle, the product of exactly two parts
+    fn default() -> Self {
+        Self::new(PartCount::Exactly(2), Aggregation::Product)
+    }
+}
+
+impl GearRule {
+    pub fn new(parts: PartCount, aggregation: Aggregation) -> Self {
+        Self { parts, aggregation }
+    }
+
+    /// Value of a gear surrounded by `parts`, `None` if it does not count
+    pub fn apply(&self, parts: &[u64]) -> Option<u64> {
+        (self.parts.matches(parts.len())).then(|| self.aggregation.apply(parts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematic::Schematic;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(PartCount::Exactly(2), 2, true)]
+    #[case(PartCount::Exactly(2), 3, false)]
+    #[case(PartCount::AtLeast(2), 3, true)]
+    #[case(PartCount::AtLeast(2), 1, false)]
+    #[case(PartCount::Between(1..=3), 0, false)]
+    #[case(PartCount::Between(1..=3), 3, true)]
+    #[case(PartCount::Between(1..=3), 4, false)]
+    fn test_part_count(#[case] parts: PartCount, #[case] count: usize, #[case] expected: bool) {
+        assert_eq!(parts.matches(count), expected);
+    }
+
+    #[rstest]
+    #[case(Aggregation::Product, &[2, 3, 4], 24)]
+    #[case(Aggregation::Sum, &[2, 3, 4], 9)]
+    #[case(Aggregation::Max, &[2, 4, 3], 4)]
+    #[case(Aggregation::Max, &[], 0)]
+    #[case(Aggregation::custom(|parts| parts.iter().min().map_or(0, |min| min * 10)), &[2, 4, 3], 20)]
+    fn test_aggregation(
+        #[case] aggregation: Aggregation,
+        #[case] parts: &[u64],
+        #[case] expected: u64,
+    ) {
+        assert_eq!(aggregation.apply(parts), expected);
+    }
+
+    #[rstest]
+    #[case(GearRule::default(), 467835)]
+    #[case(GearRule::new(PartCount::Exactly(1), Aggregation::Product), 617)]
+    #[case(GearRule::new(PartCount::AtLeast(1), Aggregation::Sum), 467 + 35 + 617 + 755 + 598)]
+    #[case(GearRule::new(PartCount::Between(1..=2), Aggregation::Max), 467 + 617 + 755)]
+    #[case(GearRule::new(PartCount::Exactly(3), Aggregation::Product), 0)]
+    fn test_evaluate_gears(#[case] rule: GearRule, #[case] expected: u64) {
+        let schematic = Schematic::new(include_str!("example.txt"));
+        assert_eq!(schematic.evaluate_gears(&rule), expected);
+    }
+}
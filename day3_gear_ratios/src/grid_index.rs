@@ -0,0 +1,192 @@
+use crate::gear_rule::GearRule;
+use crate::neighbourhood::Neighbourhood;
+use crate::schematic::{Schematic, SchematicIndex, Value};
+
+/// Dense counterpart of the token map of [`Schematic`].
+///
+/// Every cell of the grid holds the id of the token covering it, so the token at any cell
+/// is found in constant time instead of with range scans over the rows around a number.
+/// Tokens touch each other under the neighbourhood of the schematic, as in [`Schematic`].
+#[derive(Debug)]
+pub struct GridIndex {
+    width: usize,
+    height: usize,
+    // id of the token covering every cell, row by row
+    cells: Vec<Option<u32>>,
+    tokens: Vec<(SchematicIndex, Value)>,
+    neighbourhood: Neighbourhood,
+}
+
+impl GridIndex {
+    pub fn new(schematic: &Schematic) -> Self {
+        let tokens: Vec<(SchematicIndex, Value)> = schematic
+            .tokens()
+            .map(|(idx, value)| (idx, value.clone()))
+            .collect();
+
+        let height = tokens.last().map_or(0, |((row, _), _)| row + 1);
+        let width = (tokens.iter())
+            .map(|((_, (_, end)), _)| *end)
+            .max()
+            .unwrap_or(0);
+
+        let mut cells = vec![None; width * height];
+        for (id, ((row, (start, end)), _)) in tokens.iter().enumerate() {
+            let id = u32::try_from(id).expect("fewer than 2^32 tokens");
+            cells[row * width + start..row * width + end].fill(Some(id));
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+            tokens,
+            neighbourhood: schematic.neighbourhood(),
+        }
+    }
+
+    /// Token covering the cell, `None` for a period or a cell outside of the grid
+    pub fn token_at(&self, row: usize, col: usize) -> Option<(SchematicIndex, &Value)> {
+        let id = self.token_id_at(row, col)?;
+        let (idx, value) = &self.tokens[id as usize];
+        Some((*idx, value))
+    }
+
+    fn token_id_at(&self, row: usize, col: usize) -> Option<u32> {
+        if row >= self.height || col >= self.width {
+            return None;
+        }
+        self.cells[row * self.width + col]
+    }
+
+    /// Positions in [`Schematic::tokens`] of the tokens touching the token at `idx`, in order
+    pub(crate) fn adjacent_token_ids(&self, idx: SchematicIndex) -> Vec<usize> {
+        let mut ids: Vec<usize> = (self.neighbourhood.cells(idx).into_iter())
+            .filter_map(|(row, col)| self.token_id_at(row, col))
+            .map(|id| id as usize)
+            .filter(|id| self.tokens[*id].0 != idx)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    // tokens touching the token at `idx` under the neighbourhood of the schematic
+    fn adjacent_tokens(&self, idx: SchematicIndex) -> impl Iterator<Item = &Value> {
+        (self.adjacent_token_ids(idx).into_iter()).map(|id| &self.tokens[id].1)
+    }
+
+    pub fn sum_eligible_numbers(&self) -> u64 {
+        self.tokens
+            .iter()
+            .filter(|(idx, _)| self.adjacent_tokens(*idx).any(Value::is_symbol))
+            .filter_map(|(_, value)| value.get_num())
+            .sum()
+    }
+
+    pub fn gear_ratio(&self) -> u64 {
+        self.evaluate_gears(&GearRule::default())
+    }
+
+    /// Sum of the values of the `*` gears counting under `rule`
+    pub fn evaluate_gears(&self, rule: &GearRule) -> u64 {
+        self.tokens
+            .iter()
+            .filter(|(_, value)| value.is_gear())
+            .filter_map(|(idx, _)| {
+                let parts: Vec<u64> = self
+                    .adjacent_tokens(*idx)
+                    .filter_map(Value::get_num)
+                    .collect();
+                rule.apply(&parts)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gear_rule::{Aggregation, PartCount};
+    use crate::neighbourhood::Shape;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0, Some(((0, (0, 3)), Value::Num(467))))]
+    #[case(0, 2, Some(((0, (0, 3)), Value::Num(467))))]
+    #[case(0, 3, None)]
+    #[case(1, 3, Some(((1, (3, 4)), Value::Symbol('*'))))]
+    #[case(9, 7, Some(((9, (5, 8)), Value::Num(598))))]
+    #[case(9, 9, None)]
+    #[case(10, 0, None)]
+    #[case(0, 10, None)]
+    fn test_token_at(
+        #[case] row: usize,
+        #[case] col: usize,
+        #[case] expected: Option<(SchematicIndex, Value)>,
+    ) {
+        let index = GridIndex::new(&Schematic::new(include_str!("example.txt")));
+
+        assert_eq!(
+            index.token_at(row, col),
+            expected.as_ref().map(|(idx, value)| (*idx, value))
+        );
+    }
+
+    #[rstest]
+    #[case(include_str!("example.txt"))]
+    #[case(include_str!("input.txt"))]
+    #[case("")]
+    #[case("12*34\n.....\n56#..")]
+    fn test_same_as_schematic(#[case] input: &str) {
+        let schematic = Schematic::new(input);
+        let index = GridIndex::new(&schematic);
+
+        assert_eq!(
+            index.sum_eligible_numbers(),
+            schematic.sum_eligible_numbers()
+        );
+        assert_eq!(index.gear_ratio(), schematic.gear_ratio());
+    }
+
+    #[rstest]
+    #[case("1.\n.*", Neighbourhood::new(Shape::VonNeumann { radius: 1 }), false)]
+    #[case("12*..\n...3.\n.....\n...7.", Neighbourhood::new(Shape::VonNeumann { radius: 2 }), false)]
+    #[case("12*..\n...3.\n.....\n...7.", Neighbourhood::new(Shape::Moore { radius: 2 }), false)]
+    #[case("1.*2.\n.....", Neighbourhood::new(Shape::Row { radius: 1 }), false)]
+    #[case("1.*2.\n.....", Neighbourhood::new(Shape::Row { radius: 2 }), false)]
+    #[case("12*..\n...3.\n.....\n...7.", Neighbourhood::default(), true)]
+    #[case("1.....\n....*.\n......", Neighbourhood::new(Shape::Moore { radius: 2 }), true)]
+    #[case(include_str!("example.txt"), Neighbourhood::new(Shape::VonNeumann { radius: 2 }), true)]
+    fn test_same_as_schematic_with_neighbourhood(
+        #[case] input: &str,
+        #[case] neighbourhood: Neighbourhood,
+        #[case] wrapping_edges: bool,
+    ) {
+        let schematic = Schematic::new(input).with_neighbourhood(neighbourhood);
+        let schematic = if wrapping_edges {
+            schematic.with_wrapping_edges()
+        } else {
+            schematic
+        };
+        let index = GridIndex::new(&schematic);
+
+        assert_eq!(
+            index.sum_eligible_numbers(),
+            schematic.sum_eligible_numbers()
+        );
+        assert_eq!(index.gear_ratio(), schematic.gear_ratio());
+    }
+
+    #[rstest]
+    #[case(GearRule::new(PartCount::Exactly(1), Aggregation::Product))]
+    #[case(GearRule::new(PartCount::AtLeast(1), Aggregation::Sum))]
+    #[case(GearRule::new(PartCount::Between(1..=3), Aggregation::Max))]
+    #[case(GearRule::new(PartCount::Exactly(2), Aggregation::custom(|parts| parts.iter().min().copied().unwrap_or(0))))]
+    fn test_same_gears_as_schematic(#[case] rule: GearRule) {
+        let schematic = Schematic::new(include_str!("input.txt"));
+        let index = GridIndex::new(&schematic);
+
+        assert_eq!(index.evaluate_gears(&rule), schematic.evaluate_gears(&rule));
+    }
+}
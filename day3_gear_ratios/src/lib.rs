@@ -0,0 +1,9 @@
+pub mod adjacency;
+pub mod components;
+pub mod editing;
+pub mod gear_rule;
+pub mod grid_index;
+pub mod neighbourhood;
+pub mod render;
+pub mod schematic;
+pub mod symbol_rules;
@@ -0,0 +1,219 @@
+use crate::schematic::SchematicIndex;
+
+/// Cells around a cell that touch it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    /// Cells at most `radius` rows and `radius` columns away, diagonals included
+    Moore { radius: usize },
+    /// Cells at most `radius` steps away along rows and columns, without diagonals
+    VonNeumann { radius: usize },
+    /// Cells of the same row at most `radius` columns away
+    Row { radius: usize },
+}
+
+/// Which tokens touch each other, for part numbers and gears alike.
+///
+/// Two tokens touch if any cell of one is in the shape around any cell of the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighbourhood {
+    shape: Shape,
+    // rows and columns of the grid when its edges wrap around
+    torus: Option<(usize, usize)>,
+}
+
+impl Default for Neighbourhood {
+    /// The 8 cells around a cell, as in the puzzle
+    fn default() -> Self {
+        Self::new(Shape::Moore { radius: 1 })
+    }
+}
+
+impl Neighbourhood {
+    pub fn new(shape: Shape) -> Self {
+        Self { shape, torus: None }
+    }
+
+    // wraps the edges of a grid of `height` rows and `width` columns around, the size being
+    // the one of the schematic, see `Schematic::with_wrapping_edges`
+    pub(crate) fn wrapping(self, height: usize, width: usize) -> Self {
+        assert!(height > 0 && width > 0, "empty grids cannot wrap around");
+        Self {
+            torus: Some((height, width)),
+            ..self
+        }
+    }
+
+    pub fn touches(&self, a: SchematicIndex, b: SchematicIndex) -> bool {
+        let (row_a, (start_a, end_a)) = a;
+        let (row_b, (start_b, end_b)) = b;
+
+        let rows = distance(row_a, row_b, self.torus.map(|(height, _)| height));
+        let cols = (start_a..end_a)
+            .flat_map(|col_a| {
+                (start_b..end_b)
+                    .map(move |col_b| distance(col_a, col_b, self.torus.map(|(_, width)| width)))
+            })
+            .min()
+            .unwrap_or(usize::MAX);
+
+        match self.shape {
+            Shape::Moore { radius } => rows <= radius && cols <= radius,
+            Shape::VonNeumann { radius } => rows.saturating_add(cols) <= radius,
+            Shape::Row { radius } => rows == 0 && cols <= radius,
+        }
+    }
+
+    /// Rows a token of `row` can touch, in order
+    pub(crate) fn rows(&self, row: usize) -> Vec<usize> {
+        let reach = match self.shape {
+            Shape::Moore { radius } | Shape::VonNeumann { radius } => radius,
+            Shape::Row { .. } => 0,
+        };
+
+        match self.torus {
+            None => (row.saturating_sub(reach)..=row.saturating_add(reach)).collect(),
+            Some((height, _)) => {
+                let mut rows: Vec<usize> = (0..=reach.min(height))
+                    .flat_map(|d| [(row + d) % height, (row + height - d % height) % height])
+                    .collect();
+                rows.sort_unstable();
+                rows.dedup();
+                rows
+            }
+        }
+    }
+
+    /// Cells a token at `idx` touches, its own cells included, row by row.
+    ///
+    /// Cells past the edges of a grid that does not wrap around are not left out.
+    pub(crate) fn cells(&self, idx: SchematicIndex) -> Vec<(usize, usize)> {
+        let (row, (start, end)) = idx;
+
+        let mut cells = Vec::new();
+        for other_row in self.rows(row) {
+            let rows = distance(row, other_row, self.torus.map(|(height, _)| height));
+            let reach = match self.shape {
+                Shape::Moore { radius } | Shape::Row { radius } => radius,
+                Shape::VonNeumann { radius } => radius.saturating_sub(rows),
+            };
+
+            match self.torus {
+                None => cells.extend(
+                    (start.saturating_sub(reach)..end.saturating_add(reach))
+                        .map(|col| (other_row, col)),
+                ),
+                Some((_, width)) => {
+                    let span = end - start + 2 * reach;
+                    let first = start + width - reach % width;
+                    cells.extend((0..span.min(width)).map(|i| (other_row, (first + i) % width)));
+                }
+            }
+        }
+        cells
+    }
+}
+
+// distance between two rows or two columns, going around the edges of a grid of `size` if any
+fn distance(a: usize, b: usize, size: Option<usize>) -> usize {
+    match size {
+        Some(size) => {
+            let d = a.abs_diff(b) % size;
+            d.min(size - d)
+        }
+        None => a.abs_diff(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematic::Schematic;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Neighbourhood::default(), (0, (0, 3)), (1, (3, 4)), true)]
+    #[case(Neighbourhood::default(), (0, (0, 3)), (1, (4, 5)), false)]
+    #[case(Neighbourhood::new(Shape::Moore { radius: 2 }), (0, (0, 3)), (2, (4, 5)), true)]
+    #[case(Neighbourhood::new(Shape::VonNeumann { radius: 1 }), (0, (0, 3)), (1, (3, 4)), false)]
+    #[case(Neighbourhood::new(Shape::VonNeumann { radius: 1 }), (0, (0, 3)), (1, (2, 3)), true)]
+    #[case(Neighbourhood::new(Shape::VonNeumann { radius: 2 }), (0, (0, 3)), (1, (3, 4)), true)]
+    #[case(Neighbourhood::new(Shape::Row { radius: 2 }), (0, (0, 3)), (0, (4, 5)), true)]
+    #[case(Neighbourhood::new(Shape::Row { radius: 2 }), (0, (0, 3)), (1, (3, 4)), false)]
+    #[case(Neighbourhood::default(), (0, (0, 1)), (9, (9, 10)), false)]
+    #[case(Neighbourhood::default().wrapping(10, 10), (0, (0, 1)), (9, (9, 10)), true)]
+    #[case(Neighbourhood::default().wrapping(10, 10), (0, (0, 1)), (8, (9, 10)), false)]
+    fn test_touches(
+        #[case] neighbourhood: Neighbourhood,
+        #[case] a: SchematicIndex,
+        #[case] b: SchematicIndex,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(neighbourhood.touches(a, b), expected);
+        assert_eq!(neighbourhood.touches(b, a), expected);
+    }
+
+    #[rstest]
+    #[case(Neighbourhood::default(), 0, vec![0, 1])]
+    #[case(Neighbourhood::new(Shape::Moore { radius: 2 }), 3, vec![1, 2, 3, 4, 5])]
+    #[case(Neighbourhood::new(Shape::Row { radius: 2 }), 3, vec![3])]
+    #[case(Neighbourhood::default().wrapping(5, 5), 0, vec![0, 1, 4])]
+    #[case(Neighbourhood::new(Shape::Moore { radius: 3 }).wrapping(3, 3), 1, vec![0, 1, 2])]
+    fn test_rows(
+        #[case] neighbourhood: Neighbourhood,
+        #[case] row: usize,
+        #[case] expected: Vec<usize>,
+    ) {
+        assert_eq!(neighbourhood.rows(row), expected);
+    }
+
+    #[rstest]
+    #[case(Neighbourhood::default(), 12 + 3, 12 * 3)]
+    #[case(Neighbourhood::new(Shape::VonNeumann { radius: 1 }), 12, 0)]
+    #[case(Neighbourhood::new(Shape::Row { radius: 1 }), 12, 0)]
+    #[case(Neighbourhood::new(Shape::Moore { radius: 2 }), 12 + 3, 12 * 3)]
+    fn test_schematic_totals(
+        #[case] neighbourhood: Neighbourhood,
+        #[case] part_sum: u64,
+        #[case] gear_ratio: u64,
+    ) {
+        let schematic =
+            Schematic::new("12*..\n...3.\n.....\n...7.").with_neighbourhood(neighbourhood);
+
+        assert_eq!(schematic.sum_eligible_numbers(), part_sum);
+        assert_eq!(schematic.gear_ratio(), gear_ratio);
+    }
+
+    #[rstest]
+    #[case("12*..\n...3.\n.....\n...7.", 12 + 3 + 7, 0)]
+    // trailing periods count in the size of the grid
+    #[case("1....\n....*", 1, 0)]
+    #[case("1.....\n....*.", 0, 0)]
+    #[case("1....\n.....\n.....\n....*", 1, 0)]
+    #[case("1....\n.....\n.....\n....*\n.....", 0, 0)]
+    fn test_wrapping_edges(#[case] input: &str, #[case] part_sum: u64, #[case] gear_ratio: u64) {
+        let schematic = Schematic::new(input).with_wrapping_edges();
+
+        assert_eq!(schematic.sum_eligible_numbers(), part_sum);
+        assert_eq!(schematic.gear_ratio(), gear_ratio);
+    }
+
+    #[rstest]
+    #[case(Neighbourhood::default(), (0, (1, 3)), vec![(0, 0), (0, 1), (0, 2), (0, 3), (1, 0), (1, 1), (1, 2), (1, 3)])]
+    #[case(Neighbourhood::new(Shape::VonNeumann { radius: 1 }), (1, (1, 2)), vec![(0, 1), (1, 0), (1, 1), (1, 2), (2, 1)])]
+    #[case(Neighbourhood::new(Shape::Row { radius: 2 }), (0, (0, 1)), vec![(0, 0), (0, 1), (0, 2)])]
+    #[case(Neighbourhood::default().wrapping(3, 4), (0, (0, 1)), vec![(0, 3), (0, 0), (0, 1), (1, 3), (1, 0), (1, 1), (2, 3), (2, 0), (2, 1)])]
+    #[case(Neighbourhood::new(Shape::Row { radius: 3 }).wrapping(1, 4), (0, (1, 3)), vec![(0, 2), (0, 3), (0, 0), (0, 1)])]
+    fn test_cells(
+        #[case] neighbourhood: Neighbourhood,
+        #[case] idx: SchematicIndex,
+        #[case] expected: Vec<(usize, usize)>,
+    ) {
+        assert_eq!(neighbourhood.cells(idx), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty grids cannot wrap around")]
+    fn test_wrapping_empty_grid() {
+        Neighbourhood::default().wrapping(0, 5);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::gear_rule::GearRule;
+use crate::schematic::{Schematic, SchematicIndex, GEAR};
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+const HTML_HEAD: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Schematic</title>
+<style>
+body { background: #1e1e1e; color: #6e6e6e; }
+pre { font-family: monospace; line-height: 1.2; }
+span:hover { background: #444444; }
+.part { color: #66bb6a; }
+.ignored { color: #e57373; }
+.gear { color: #ffd54f; font-weight: bold; }
+.symbol { color: #4dd0e1; }
+</style>
+</head>
+<body>
+<pre>
+"#;
+
+const HTML_TAIL: &str = "</pre>
+</body>
+</html>
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenClass {
+    /// Number touching a symbol
+    PartNumber,
+    /// Number touching no symbol
+    IgnoredNumber,
+    /// Gear counting under the puzzle rule, with exactly two parts
+    Gear,
+    /// Any other symbol, including gears with a different number of parts
+    Symbol,
+}
+
+impl TokenClass {
+    fn ansi(self) -> &'static str {
+        match self {
+            Self::PartNumber => "\x1b[32m",
+            Self::IgnoredNumber => "\x1b[2;31m",
+            Self::Gear => "\x1b[1;33m",
+            Self::Symbol => "\x1b[36m",
+        }
+    }
+
+    fn css_class(self) -> &'static str {
+        match self {
+            Self::PartNumber => "part",
+            Self::IgnoredNumber => "ignored",
+            Self::Gear => "gear",
+            Self::Symbol => "symbol",
+        }
+    }
+}
+
+/// Token with its class and the tokens it touches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub idx: SchematicIndex,
+    pub text: String,
+    pub class: TokenClass,
+    pub neighbours: Vec<(SchematicIndex, String)>,
+}
+
+impl Annotation {
+    fn tooltip(&self) -> String {
+        let (row, (start, end)) = self.idx;
+        let neighbours: Vec<String> = (self.neighbours.iter())
+            .map(|((row, (start, _)), text)| format!("{text} at {row}:{start}"))
+            .collect();
+        let neighbours = if neighbours.is_empty() {
+            "nothing".to_string()
+        } else {
+            neighbours.join(", ")
+        };
+
+        format!(
+            "{} at row {row}, columns {start} to {}, touches {neighbours}",
+            self.text,
+            end - 1
+        )
+    }
+}
+
+/// Every token of the schematic with its annotation, ordered by position
+pub fn annotate(schematic: &Schematic) -> Vec<Annotation> {
+    let graph = schematic.adjacency_graph();
+    let gear_rule = GearRule::default();
+    // numbers keep the width they are written with, leading zeros included
+    let number_text = |idx: SchematicIndex, value: u64| {
+        let (_, (start, end)) = idx;
+        format!("{value:0width$}", width = end - start)
+    };
+
+    let numbers = graph.numbers().map(|(number, symbols)| {
+        let neighbours: Vec<(SchematicIndex, String)> = symbols
+            .map(|symbol| (symbol.idx, symbol.kind.to_string()))
+            .collect();
+        let class = if neighbours.is_empty() {
+            TokenClass::IgnoredNumber
+        } else {
+            TokenClass::PartNumber
+        };
+        Annotation {
+            idx: number.idx,
+            text: number_text(number.idx, number.value),
+            class,
+            neighbours,
+        }
+    });
+
+    let symbols = graph.symbols().map(|(symbol, numbers)| {
+        let numbers: Vec<_> = numbers.collect();
+        let parts: Vec<u64> = numbers.iter().map(|number| number.value).collect();
+        let class = if symbol.kind == GEAR && gear_rule.apply(&parts).is_some() {
+            TokenClass::Gear
+        } else {
+            TokenClass::Symbol
+        };
+        Annotation {
+            idx: symbol.idx,
+            text: symbol.kind.to_string(),
+            class,
+            neighbours: (numbers.iter())
+                .map(|number| (number.idx, number_text(number.idx, number.value)))
+                .collect(),
+        }
+    });
+
+    let mut annotations: Vec<Annotation> = numbers.chain(symbols).collect();
+    annotations.sort_unstable_by_key(|annotation| annotation.idx);
+    annotations
+}
+
+/// Schematic with colored tokens for a terminal
+pub fn render_ansi(schematic: &Schematic) -> String {
+    render(schematic, |annotation| {
+        format!("{}{}{ANSI_RESET}", annotation.class.ansi(), annotation.text)
+    })
+}
+
+/// Standalone HTML page of the schematic, showing the tokens each token touches on hover
+pub fn render_html(schematic: &Schematic) -> String {
+    let body = render(schematic, |annotation| {
+        format!(
+            r#"<span class="{}" title="{}">{}</span>"#,
+            annotation.class.css_class(),
+            escape_html(&annotation.tooltip()),
+            escape_html(&annotation.text)
+        )
+    });
+    format!("{HTML_HEAD}{body}{HTML_TAIL}")
+}
+
+// every line of the schematic, periods filling the cells between tokens and the rows up to
+// the width of the schematic
+fn render(schematic: &Schematic, token: impl Fn(&Annotation) -> String) -> String {
+    let annotations = annotate(schematic);
+    let width = schematic.width();
+
+    let mut annotations = annotations.iter().peekable();
+    let mut output = String::new();
+    for row in 0..schematic.height() {
+        let mut cursor = 0;
+        while let Some(annotation) = annotations.next_if(|annotation| annotation.idx.0 == row) {
+            let (_, (start, end)) = annotation.idx;
+            output.push_str(&".".repeat(start - cursor));
+            output.push_str(&token(annotation));
+            cursor = end;
+        }
+        output.push_str(&".".repeat(width - cursor));
+        output.push('\n');
+    }
+    output
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn strip_ansi(text: &str) -> String {
+        let mut stripped = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                stripped.push(c);
+            }
+        }
+        stripped
+    }
+
+    #[rstest]
+    #[case((0, (0, 3)), TokenClass::PartNumber)]
+    #[case((0, (5, 8)), TokenClass::IgnoredNumber)]
+    #[case((1, (3, 4)), TokenClass::Gear)]
+    #[case((3, (6, 7)), TokenClass::Symbol)]
+    #[case((4, (3, 4)), TokenClass::Symbol)]
+    fn test_annotate(#[case] idx: SchematicIndex, #[case] expected: TokenClass) {
+        let schematic = Schematic::new(include_str!("example.txt"));
+
+        let annotations = annotate(&schematic);
+
+        let annotation = (annotations.iter())
+            .find(|annotation| annotation.idx == idx)
+            .unwrap();
+        assert_eq!(annotation.class, expected);
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let schematic = Schematic::new("467.\n...*\n..35");
+
+        let rendered = render_ansi(&schematic);
+
+        assert_eq!(
+            rendered,
+            "\x1b[32m467\x1b[0m.\n...\x1b[1;33m*\x1b[0m\n..\x1b[32m35\x1b[0m\n"
+        );
+    }
+
+    #[rstest]
+    #[case("..007.\n.#....\n......\n12..9<")]
+    #[case("..1...\n.#....\n......")]
+    #[case("......\n......")]
+    fn test_render_ansi_keeps_text(#[case] input: &str) {
+        let rendered = render_ansi(&Schematic::new(input));
+
+        assert_eq!(strip_ansi(&rendered), format!("{input}\n"));
+    }
+
+    #[test]
+    fn test_render_html() {
+        let schematic = Schematic::new("12<");
+
+        let rendered = render_html(&schematic);
+
+        assert!(rendered.starts_with("<!DOCTYPE html>"));
+        assert!(rendered.contains(
+            r#"<span class="part" title="12 at row 0, columns 0 to 1, touches &lt; at 0:2">12</span>"#
+        ));
+        assert!(rendered.contains(
+            r#"<span class="symbol" title="&lt; at row 0, columns 2 to 2, touches 12 at 0:0">&lt;</span>
+</pre>"#
+        ));
+    }
+}
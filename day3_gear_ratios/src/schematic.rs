@@ -0,0 +1,373 @@
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+
+use crate::adjacency::AdjacencyGraph;
+use crate::components::{components, Component};
+use crate::gear_rule::GearRule;
+use crate::neighbourhood::Neighbourhood;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{none_of, u64},
+    combinator::map,
+    multi::many0,
+    IResult,
+};
+
+fn num(input: &str) -> IResult<&str, Value> {
+    let (rem, n) = u64(input)?;
+    Ok((rem, Value::Num(n)))
+}
+
+fn periods_count(input: &str) -> IResult<&str, usize> {
+    let (rem, periods) = many0(tag("."))(input)?;
+    Ok((rem, periods.len()))
+}
+
+fn symbol(input: &str) -> IResult<&str, Value> {
+    map(none_of("0123456789."), Value::Symbol)(input)
+}
+
+/// Symbol of the gears of the puzzle
+pub const GEAR: char = '*';
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Value {
+    Num(u64),
+    Symbol(char),
+}
+
+impl Value {
+    pub fn is_symbol(&self) -> bool {
+        matches!(self, Value::Symbol(_))
+    }
+
+    pub fn is_gear(&self) -> bool {
+        matches!(self, Value::Symbol(GEAR))
+    }
+
+    pub fn get_symbol(&self) -> Option<char> {
+        match self {
+            Value::Symbol(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    pub fn get_num(&self) -> Option<u64> {
+        match self {
+            Value::Num(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// Row and span of columns of a token
+pub type SchematicIndex = (usize, (usize, usize));
+#[derive(Debug)]
+pub struct Schematic {
+    value_map: BTreeMap<SchematicIndex, Value>,
+    // rows and columns of the grid, periods included
+    height: usize,
+    width: usize,
+    neighbourhood: Neighbourhood,
+    wrapping_edges: bool,
+    // built on first use, and dropped whenever the tokens or the neighbourhood change
+    graph: OnceCell<AdjacencyGraph>,
+}
+
+impl Schematic {
+    fn empty() -> Self {
+        Self {
+            value_map: BTreeMap::new(),
+            height: 0,
+            width: 0,
+            neighbourhood: Neighbourhood::default(),
+            wrapping_edges: false,
+            graph: OnceCell::new(),
+        }
+    }
+
+    pub fn new(input: &str) -> Self {
+        let mut schematic = Self::empty();
+        input.lines().enumerate().for_each(|(row, line)| {
+            schematic.insert_row(row, line);
+        });
+        schematic
+    }
+
+    fn insert(&mut self, row: usize, span: (usize, usize), value: Value) {
+        self.value_map.insert((row, span), value);
+    }
+
+    fn insert_row(&mut self, row: usize, input: &str) {
+        self.graph.take();
+        let mut cursor = 0;
+        let mut input = input;
+
+        while !input.is_empty() {
+            if let Ok((rem, dist)) = periods_count(input) {
+                cursor += dist;
+                input = rem;
+            }
+
+            if let Ok((rem, n)) = num(input) {
+                let dist = columns(input, rem);
+                self.insert(row, (cursor, cursor + dist), n);
+                cursor += dist;
+                input = rem;
+            }
+
+            if let Ok((rem, s)) = symbol(input) {
+                let dist = columns(input, rem);
+                self.insert(row, (cursor, cursor + dist), s);
+
+                cursor += dist;
+                input = rem;
+            }
+        }
+
+        self.height = self.height.max(row + 1);
+        self.width = self.width.max(cursor);
+    }
+
+    /// Tokens touch each other under `neighbourhood` instead of the 8 cells around every cell
+    pub fn with_neighbourhood(self, neighbourhood: Neighbourhood) -> Self {
+        Self {
+            neighbourhood,
+            graph: OnceCell::new(),
+            ..self
+        }
+    }
+
+    /// The last row touches the first one and the last column the first one, under any
+    /// neighbourhood
+    pub fn with_wrapping_edges(self) -> Self {
+        Self {
+            wrapping_edges: true,
+            graph: OnceCell::new(),
+            ..self
+        }
+    }
+
+    /// Number of rows of the grid, trailing rows of periods included
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of columns of the widest row, trailing periods included
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Neighbourhood tokens touch each other under, wrapping around the edges of the grid
+    /// if they wrap
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        // an empty grid has no tokens to touch each other
+        if self.wrapping_edges && self.height > 0 && self.width > 0 {
+            self.neighbourhood.wrapping(self.height, self.width)
+        } else {
+            self.neighbourhood
+        }
+    }
+
+    /// Replaces the tokens of `row` with the ones of `input`
+    pub(crate) fn replace_row(&mut self, row: usize, input: &str) {
+        let idxs: Vec<SchematicIndex> = self.row_tokens(row).map(|(idx, _)| idx).collect();
+        for idx in idxs {
+            self.value_map.remove(&idx);
+        }
+        self.insert_row(row, input);
+    }
+
+    /// Moves `row` and the rows after it one row down, leaving `row` empty
+    pub(crate) fn shift_rows_down(&mut self, row: usize) {
+        self.graph.take();
+        if row < self.height {
+            self.height += 1;
+        }
+        let moved = self.value_map.split_off(&(row, (0, 0)));
+        (self.value_map).extend(
+            moved
+                .into_iter()
+                .map(|((row, span), v)| ((row + 1, span), v)),
+        );
+    }
+
+    /// Removes the tokens of `row` and moves the rows after it one row up
+    pub(crate) fn remove_row(&mut self, row: usize) {
+        self.graph.take();
+        if row < self.height {
+            self.height -= 1;
+        }
+        let mut moved = self.value_map.split_off(&(row, (0, 0)));
+        let moved = moved.split_off(&(row + 1, (0, 0)));
+        (self.value_map).extend(
+            moved
+                .into_iter()
+                .map(|((row, span), v)| ((row - 1, span), v)),
+        );
+    }
+
+    /// Every token with its position, row by row
+    pub fn tokens(&self) -> impl Iterator<Item = (SchematicIndex, &Value)> {
+        self.value_map.iter().map(|(idx, value)| (*idx, value))
+    }
+
+    pub(crate) fn row_tokens(&self, row: usize) -> impl Iterator<Item = (SchematicIndex, &Value)> {
+        (self.value_map.range((row, (0, 0))..(row + 1, (0, 0)))).map(|(idx, value)| (*idx, value))
+    }
+
+    /// Position of every gear symbol, row by row
+    pub fn gear_indices(&self) -> Vec<SchematicIndex> {
+        self.tokens()
+            .filter(|(_, value)| value.is_gear())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Tokens touching the token at `idx` under the neighbourhood of the schematic
+    pub(crate) fn neighbours(&self, idx: SchematicIndex) -> Vec<(SchematicIndex, &Value)> {
+        let (row, _) = idx;
+        let neighbourhood = self.neighbourhood();
+
+        (neighbourhood.rows(row).into_iter())
+            .flat_map(|row| self.row_tokens(row))
+            .filter(|(other, _)| *other != idx && neighbourhood.touches(idx, *other))
+            .collect()
+    }
+
+    /// Graph of the numbers and the symbols they touch, built once and reused until the
+    /// schematic changes
+    pub fn adjacency_graph(&self) -> &AdjacencyGraph {
+        self.graph.get_or_init(|| AdjacencyGraph::new(self))
+    }
+
+    /// Groups of numbers linked through the symbols they share, see [`components`]
+    pub fn components(&self) -> Vec<Component> {
+        components(self.adjacency_graph())
+    }
+
+    pub fn sum_eligible_numbers(&self) -> u64 {
+        self.adjacency_graph()
+            .part_numbers()
+            .map(|number| number.value)
+            .sum()
+    }
+
+    pub fn gear_ratio(&self) -> u64 {
+        self.evaluate_gears(&GearRule::default())
+    }
+
+    /// Sum of the values of the `*` gears counting under `rule`
+    pub fn evaluate_gears(&self, rule: &GearRule) -> u64 {
+        self.adjacency_graph()
+            .symbols()
+            .filter(|(symbol, _)| symbol.kind == GEAR)
+            .filter_map(|(_, numbers)| {
+                let parts: Vec<u64> = numbers.map(|number| number.value).collect();
+                rule.apply(&parts)
+            })
+            .sum()
+    }
+}
+
+// number of columns parsed from `input` to get to `rem`, one per character
+fn columns(input: &str, rem: &str) -> usize {
+    input[..input.len() - rem.len()].chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    type IndexedValue = Vec<((usize, (usize, usize)), Value)>;
+
+    #[rstest]
+    #[case("467..114..", 0, vec![((0, (0, 3)), Value::Num(467)), ((0, (5, 8)), Value::Num(114))])]
+    #[case("...*......", 1, vec![((1, (3, 4)), Value::Symbol('*'))])]
+    #[case("..35..633.", 2, vec![((2, (2, 4)), Value::Num(35)), ((2, (6, 9)), Value::Num(633))])]
+    #[case("......#...", 3, vec![((3, (6, 7)), Value::Symbol('#'))])]
+    #[case("617*......", 4, vec![((4, (0, 3)), Value::Num(617)), ((4, (3, 4)), Value::Symbol('*'))])]
+    #[case(".....+.58.", 5, vec![((5, (5, 6)), Value::Symbol('+')), ((5, (7, 9)), Value::Num(58))])]
+    #[case("..592.....", 6, vec![((6, (2, 5)), Value::Num(592))])]
+    #[case("......755.", 7, vec![((7, (6, 9)), Value::Num(755))])]
+    #[case("...$.*....", 8, vec![((8, (3, 4)), Value::Symbol('$')), ((8, (5, 6)), Value::Symbol('*'))])]
+    #[case(".664.598.." , 9, vec![((9, (1, 4)), Value::Num(664)), ((9, (5, 8)), Value::Num(598))])]
+    #[case("§.12→.3", 0, vec![((0, (0, 1)), Value::Symbol('§')), ((0, (2, 4)), Value::Num(12)), ((0, (4, 5)), Value::Symbol('→')), ((0, (6, 7)), Value::Num(3))])]
+    #[case("→→→..7", 1, vec![((1, (0, 1)), Value::Symbol('→')), ((1, (1, 2)), Value::Symbol('→')), ((1, (2, 3)), Value::Symbol('→')), ((1, (5, 6)), Value::Num(7))])]
+    fn test_insert_rows(#[case] input: &str, #[case] row: usize, #[case] expected: IndexedValue) {
+        let mut schematic = Schematic::empty();
+        schematic.insert_row(row, input);
+        assert_eq!(
+            schematic.value_map.into_iter().collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(0 ,vec![467])]
+    #[case(1 ,vec![])]
+    #[case(2 ,vec![35, 633])]
+    #[case(3 ,vec![])]
+    #[case(4 ,vec![617])]
+    #[case(5 ,vec![])]
+    #[case(6 ,vec![592])]
+    #[case(7 ,vec![755])]
+    #[case(8 ,vec![])]
+    #[case(9 ,vec![664, 598])]
+    fn test_symbol_adjecent_number_for_row(#[case] row: usize, #[case] expected: Vec<u64>) {
+        let schematic = Schematic::new(include_str!("example.txt"));
+        let part_numbers: Vec<u64> = (schematic.adjacency_graph().part_numbers())
+            .filter(|number| number.idx.0 == row)
+            .map(|number| number.value)
+            .collect();
+        assert_eq!(part_numbers, expected);
+    }
+
+    #[test]
+    fn test_sum_eligible_numbers() {
+        let schematic = Schematic::new(include_str!("example.txt"));
+        assert_eq!(schematic.sum_eligible_numbers(), 4361);
+    }
+
+    #[test]
+    fn test_gears_indices() {
+        let schematic = Schematic::new(include_str!("example.txt"));
+        assert_eq!(
+            schematic.gear_indices(),
+            vec![(1, (3, 4)), (4, (3, 4)), (8, (5, 6)),]
+        );
+    }
+
+    #[rstest]
+    #[case((1, (3, 4)), vec![467, 35])]
+    #[case((4, (3, 4)), vec![617])]
+    #[case((8, (5, 6)), vec![755, 598])]
+    fn test_nums_around(#[case] idx: SchematicIndex, #[case] expected: Vec<u64>) {
+        let schematic = Schematic::new(include_str!("example.txt"));
+        let nums: Vec<u64> = (schematic.neighbours(idx).into_iter())
+            .filter_map(|(_, v)| v.get_num())
+            .collect();
+        assert_eq!(nums, expected);
+    }
+
+    #[rstest]
+    #[case("→→→..\n....7", 0, 0)]
+    #[case("→→→..\n...7.", 7, 0)]
+    #[case("§12..\n..*..\n...34", 12 + 34, 12 * 34)]
+    #[case("12...\n→→*..\n...34", 12 + 34, 12 * 34)]
+    #[case("12.→.\n→→.*.\n...34", 12 + 34, 0)]
+    fn test_non_ascii_symbols(#[case] input: &str, #[case] part_sum: u64, #[case] gear_ratio: u64) {
+        let schematic = Schematic::new(input);
+        assert_eq!(schematic.sum_eligible_numbers(), part_sum);
+        assert_eq!(schematic.gear_ratio(), gear_ratio);
+    }
+
+    #[test]
+    fn test_gear_ratio() {
+        let schematic = Schematic::new(include_str!("example.txt"));
+        assert_eq!(schematic.gear_ratio(), 467835);
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use crate::gear_rule::GearRule;
+use crate::schematic::{Schematic, GEAR};
+
+/// How a symbol turns the numbers around it into a value
+#[derive(Debug, Clone)]
+pub enum SymbolRule {
+    /// Value of a gear under the rule, like the gears of the puzzle
+    Gear(GearRule),
+    /// Sum of the numbers times `factor`
+    Multiplier { factor: u64 },
+    /// Sum of the numbers when there are at least `min_parts` of them
+    Required { min_parts: usize },
+}
+
+impl SymbolRule {
+    /// Value of a symbol surrounded by `parts`, `None` if the rule is not met
+    pub fn apply(&self, parts: &[u64]) -> Option<u64> {
+        match self {
+            Self::Gear(rule) => rule.apply(parts),
+            Self::Multiplier { factor } => Some(parts.iter().sum::<u64>() * factor),
+            Self::Required { min_parts } => (parts.len() >= *min_parts).then(|| parts.iter().sum()),
+        }
+    }
+}
+
+/// Rule of every symbol kind, kinds without a rule are never met
+#[derive(Debug, Clone)]
+pub struct SymbolRules {
+    rules: BTreeMap<char, SymbolRule>,
+}
+
+/// Values of the symbols of one kind
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolTotals {
+    /// number of symbols of the kind
+    pub count: usize,
+    /// number of them meeting the rule of the kind
+    pub matched: usize,
+    /// sum of the values of the matched symbols
+    pub total: u64,
+}
+
+impl Default for SymbolRules {
+    /// Rules of the puzzle, `*` being a gear under the default rule
+    fn default() -> Self {
+        Self::empty().rule(GEAR, SymbolRule::Gear(GearRule::default()))
+    }
+}
+
+impl SymbolRules {
+    pub fn empty() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the rule of `symbol`, replacing the previous one
+    pub fn rule(mut self, symbol: char, rule: SymbolRule) -> Self {
+        self.rules.insert(symbol, rule);
+        self
+    }
+
+    pub fn get(&self, symbol: char) -> Option<&SymbolRule> {
+        self.rules.get(&symbol)
+    }
+
+    /// Totals of every symbol kind found in the schematic
+    pub fn totals(&self, schematic: &Schematic) -> BTreeMap<char, SymbolTotals> {
+        let mut totals: BTreeMap<char, SymbolTotals> = BTreeMap::new();
+
+        for (symbol, numbers) in schematic.adjacency_graph().symbols() {
+            let totals = totals.entry(symbol.kind).or_default();
+            totals.count += 1;
+
+            let parts: Vec<u64> = numbers.map(|number| number.value).collect();
+            if let Some(value) = self.get(symbol.kind).and_then(|rule| rule.apply(&parts)) {
+                totals.matched += 1;
+                totals.total += value;
+            }
+        }
+
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(SymbolRule::Gear(GearRule::default()), &[3, 4], Some(12))]
+    #[case(SymbolRule::Gear(GearRule::default()), &[3], None)]
+    #[case(SymbolRule::Multiplier { factor: 3 }, &[3, 4], Some(21))]
+    #[case(SymbolRule::Multiplier { factor: 3 }, &[], Some(0))]
+    #[case(SymbolRule::Required { min_parts: 1 }, &[3, 4], Some(7))]
+    #[case(SymbolRule::Required { min_parts: 1 }, &[], None)]
+    fn test_apply(#[case] rule: SymbolRule, #[case] parts: &[u64], #[case] expected: Option<u64>) {
+        assert_eq!(rule.apply(parts), expected);
+    }
+
+    #[test]
+    fn test_default_totals() {
+        let schematic = Schematic::new(include_str!("example.txt"));
+
+        let totals = SymbolRules::default().totals(&schematic);
+
+        assert_eq!(totals.keys().collect::<String>(), "#$*+");
+        assert_eq!(
+            totals[&'*'],
+            SymbolTotals {
+                count: 3,
+                matched: 2,
+                total: 467835
+            }
+        );
+        assert_eq!(
+            totals[&'#'],
+            SymbolTotals {
+                count: 1,
+                matched: 0,
+                total: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_custom_totals() {
+        let schematic = Schematic::new(include_str!("example.txt"));
+        let rules = SymbolRules::default()
+            .rule('#', SymbolRule::Multiplier { factor: 3 })
+            .rule('$', SymbolRule::Required { min_parts: 1 })
+            .rule('+', SymbolRule::Required { min_parts: 2 });
+
+        let totals: Vec<(char, usize, u64)> = rules
+            .totals(&schematic)
+            .into_iter()
+            .map(|(symbol, totals)| (symbol, totals.matched, totals.total))
+            .collect();
+
+        assert_eq!(
+            totals,
+            [
+                ('#', 1, 633 * 3),
+                ('$', 1, 664),
+                ('*', 2, 467835),
+                ('+', 0, 0)
+            ]
+        );
+    }
+}
@@ -24,6 +24,7 @@ impl Race {
     ///
     /// sovling this inequality with quadratic formula, we got:
     /// > (time +- sqrt(time^2 - 4 * distance)) / 2 = h
+    ///
     /// as input are unsinged integers, the range of `h` is:
     /// > [h_lesser, h_greater]
     ///
@@ -59,14 +60,14 @@ fn parse_races(input: &str) -> IResult<&str, Vec<Race>> {
 
     let races = times
         .into_iter()
-        .zip(distances.into_iter())
+        .zip(distances)
         .map(|(time, distance)| Race::new(time, distance))
         .collect();
 
     Ok((rem, races))
 }
 
-fn u64_ignore_spaces<'a>(input: &'a str) -> IResult<&'a str, u64> {
+fn u64_ignore_spaces(input: &str) -> IResult<&str, u64> {
     map_res(separated_list1(space1, digit1), |nums: Vec<&str>| {
         nums.concat().parse::<u64>()
     })(input)